@@ -0,0 +1,46 @@
+/*
+ * Plays rounds with a "stand on 17" rule through the C interface.
+ *
+ *   cargo build --release
+ *   cc ffi/example.c -Iffi -Ltarget/release -lnanai_veintiuna_rs -o example
+ *   LD_LIBRARY_PATH=target/release ./example
+ */
+#include <stdio.h>
+#include "veintiuna.h"
+
+int main(void) {
+    VtTable *table = vt_table_new(42);
+    if (!table) {
+        return 1;
+    }
+    VtState state;
+    for (int round = 1; round <= 5; round++) {
+        if (vt_place_bet(table, 10) != VT_OK) {
+            printf("bet: %s\n", vt_last_error(table));
+            break;
+        }
+        vt_get_state(table, &state);
+        while (state.phase != VT_PHASE_BETTING && state.phase != VT_PHASE_GAME_OVER) {
+            if (state.phase == VT_PHASE_INSURANCE) {
+                vt_insurance(table, false);
+            } else {
+                const VtHand *hand = &state.hands[state.active_hand];
+                int action = hand->total < 17 ? VT_ACTION_HIT : VT_ACTION_STAND;
+                if (vt_apply_action(table, action) != VT_OK) {
+                    printf("action: %s\n", vt_last_error(table));
+                    break;
+                }
+            }
+            vt_get_state(table, &state);
+        }
+        int64_t net = 0;
+        vt_settle(table, &net);
+        printf("round %d: player %d, dealer %d, net %lld, bank %u\n", round, state.hands[0].total,
+               state.dealer_total, (long long)net, state.bank);
+    }
+    if (vt_apply_action(table, 99) != VT_OK) {
+        printf("error: %s\n", vt_last_error(table));
+    }
+    vt_table_free(table);
+    return 0;
+}
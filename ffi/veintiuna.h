@@ -0,0 +1,109 @@
+/*
+ * veintiuna.h - C interface to the nanai_veintiuna_rs blackjack engine.
+ *
+ * Link against the cdylib built by `cargo build --release`
+ * (target/release/libnanai_veintiuna_rs.so / .dylib / .dll).
+ *
+ * One table is one seat against the dealer. Handles are opaque and must
+ * not be shared between threads without a lock. Every call returns a
+ * VT_* status; on failure vt_last_error() describes it (UTF-8, Japanese).
+ */
+#ifndef VEINTIUNA_H
+#define VEINTIUNA_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes */
+#define VT_OK           0
+#define VT_ERR_NULL     1  /* a required pointer was null */
+#define VT_ERR_PHASE    2  /* the call does not fit the current phase */
+#define VT_ERR_BET      3  /* bet outside the table limits or the bank */
+#define VT_ERR_ACTION   4  /* action not legal for the hand, or unknown */
+#define VT_ERR_INTERNAL 5  /* engine failure; destroy the table */
+
+/* Phases (VtState.phase) */
+#define VT_PHASE_BETTING     0
+#define VT_PHASE_INSURANCE   1
+#define VT_PHASE_PLAYER_TURN 2
+#define VT_PHASE_GAME_OVER   3  /* bank below the table minimum */
+
+/* Actions (vt_apply_action, bits of VtState.legal_actions) */
+#define VT_ACTION_HIT         0
+#define VT_ACTION_STAND       1
+#define VT_ACTION_DOUBLE_DOWN 2
+#define VT_ACTION_SPLIT       3
+#define VT_ACTION_SURRENDER   4
+
+/* Hand outcomes (VtHand.outcome, -1 until settled) */
+#define VT_OUTCOME_BUST             0
+#define VT_OUTCOME_DEALER_BUST      1
+#define VT_OUTCOME_BLACKJACK        2
+#define VT_OUTCOME_DEALER_BLACKJACK 3
+#define VT_OUTCOME_BOTH_BLACKJACK   4
+#define VT_OUTCOME_WIN              5
+#define VT_OUTCOME_EDGE_WIN         6
+#define VT_OUTCOME_LOSE             7
+#define VT_OUTCOME_PUSH             8
+#define VT_OUTCOME_SURRENDER        9
+
+/* Cards are rank codes: 2..10, 11 = J, 12 = Q, 13 = K, 14 = A, 0 = face down */
+#define VT_MAX_CARDS 16
+#define VT_MAX_HANDS 8
+
+typedef struct VtTable VtTable;
+
+typedef struct VtHand {
+    uint8_t cards[VT_MAX_CARDS];
+    uint8_t card_count;
+    uint8_t total;
+    bool soft;
+    bool doubled;
+    bool surrendered;
+    uint32_t bet;
+    int32_t outcome;
+} VtHand;
+
+typedef struct VtState {
+    int32_t phase;
+    uint32_t bank;
+    uint32_t min_bet;
+    uint32_t max_bet;       /* already limited by the bank */
+    uint8_t dealer_cards[VT_MAX_CARDS];
+    uint8_t dealer_count;
+    uint8_t dealer_total;   /* face-up cards only */
+    uint8_t hand_count;     /* may exceed VT_MAX_HANDS; only that many are filled */
+    int8_t active_hand;     /* -1 when no hand is to act */
+    VtHand hands[VT_MAX_HANDS];
+    uint32_t legal_actions; /* 1 << VT_ACTION_* */
+    uint32_t insurance_cost;
+} VtState;
+
+/* Tables. The seed fixes the shuffle. NULL on failure. */
+VtTable *vt_table_new(uint64_t seed);
+VtTable *vt_table_new_with_config(const char *config_path, uint64_t seed);
+void vt_table_free(VtTable *table);
+
+/* Deals a round. The dealer plays and the bank is paid as soon as no
+ * decision is left, so the phase is back to BETTING after the last action. */
+int32_t vt_place_bet(VtTable *table, uint32_t amount);
+int32_t vt_insurance(VtTable *table, bool take);
+int32_t vt_apply_action(VtTable *table, int32_t action);
+
+/* Net result of the round just settled; VT_ERR_PHASE while it is in play. */
+int32_t vt_settle(VtTable *table, int64_t *net);
+
+int32_t vt_get_state(VtTable *table, VtState *out);
+
+/* Message for the last failed call; valid until the next call on the table. */
+const char *vt_last_error(const VtTable *table);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VEINTIUNA_H */
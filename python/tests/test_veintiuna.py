@@ -0,0 +1,117 @@
+"""Checks the extension against the rules the game plays.
+
+Build the module first (see python/README.md), then from the repository root:
+
+    python3 -m unittest discover -s python/tests
+"""
+
+import os
+import sys
+import tempfile
+import unittest
+
+sys.path.insert(0, os.path.join(os.path.dirname(__file__), ".."))
+
+import veintiuna  # noqa: E402
+
+
+class HandTest(unittest.TestCase):
+    def test_hand_value(self):
+        self.assertEqual(veintiuna.hand_value(["A", "K"]), 21)
+        self.assertEqual(veintiuna.hand_value(["A", "A", "9"]), 21)
+        self.assertEqual(veintiuna.hand_value(["K", "Q", "5"]), 25)
+
+    def test_soft(self):
+        self.assertTrue(veintiuna.is_soft(["A", "6"]))
+        self.assertFalse(veintiuna.is_soft(["A", "6", "K"]))
+
+    def test_unknown_card(self):
+        with self.assertRaises(ValueError):
+            veintiuna.hand_value(["1"])
+
+    def test_basic_strategy(self):
+        self.assertEqual(veintiuna.basic_strategy(["8", "8"], "6"), "split")
+        self.assertEqual(veintiuna.basic_strategy(["K", "6"], "K", ["hit", "stand"]), "hit")
+        self.assertEqual(veintiuna.basic_strategy(["K", "7"], "6"), "stand")
+
+
+class RoundTest(unittest.TestCase):
+    def test_round_to_settlement(self):
+        shoe = veintiuna.Shoe(decks=6, seed=42)
+        round_ = veintiuna.Round.deal(shoe, [10, 20])
+        while round_.pending_insurance() is not None:
+            round_.resolve_insurance(shoe, False)
+        self.assertIsNone(round_.dealer()[0])
+        while round_.active() is not None:
+            round_.apply(shoe, "stand")
+        round_.play_dealer(shoe)
+        self.assertNotIn(None, round_.dealer())
+        for spot, bet in enumerate([10, 20]):
+            self.assertIn(round_.net(spot), (-bet, 0, bet))
+            self.assertEqual(len(round_.outcomes(spot)), 1)
+
+    def test_illegal_action(self):
+        shoe = veintiuna.Shoe(seed=7)
+        round_ = veintiuna.Round.deal(shoe, [10])
+        while round_.pending_insurance() is not None:
+            round_.resolve_insurance(shoe, False)
+        if round_.active() is not None and "split" not in round_.legal_actions():
+            with self.assertRaises(RuntimeError):
+                round_.apply(shoe, "split")
+
+    def test_rules_from_config(self):
+        path = os.path.join(tempfile.gettempdir(), "veintiuna-python-%d.toml" % os.getpid())
+        with open(path, "w") as f:
+            f.write('[rules]\nblackjack_payout = "three_to_two"\n')
+        try:
+            for seed in range(500):
+                nets = []
+                for config in (None, path):
+                    shoe = veintiuna.Shoe(seed=seed)
+                    round_ = veintiuna.Round.deal(shoe, [10], config=config)
+                    while round_.pending_insurance() is not None:
+                        round_.resolve_insurance(shoe, False)
+                    while round_.active() is not None:
+                        round_.apply(shoe, "stand")
+                    round_.play_dealer(shoe)
+                    nets.append(round_.net(0))
+                if round_.outcomes(0) == ["blackjack"]:
+                    self.assertEqual(nets, [10, 15])
+                    return
+            self.fail("no natural in 500 seeds")
+        finally:
+            os.remove(path)
+
+    def test_seeded_shoe_repeats(self):
+        a = veintiuna.Shoe(seed=1)
+        b = veintiuna.Shoe(seed=1)
+        self.assertEqual([a.draw() for _ in range(20)], [b.draw() for _ in range(20)])
+
+
+class EnvTest(unittest.TestCase):
+    def test_episode(self):
+        env = veintiuna.Env(seed=3)
+        step = env.reset(seed=3)
+        while not step["done"]:
+            self.assertEqual(len(step["observation"]["action_mask"]), 5)
+            step = env.step("stand")
+        self.assertFalse(any(step["observation"]["action_mask"]))
+        with self.assertRaises(RuntimeError):
+            env.step("hit")
+
+
+class SimulationTest(unittest.TestCase):
+    def test_simulate(self):
+        report = veintiuna.simulate("martingale", sessions=20, rounds=50, seed=5)
+        self.assertEqual(report["system"], "martingale")
+        self.assertTrue(0.0 <= report["ruin_rate"] <= 1.0)
+
+    def test_risk_of_ruin(self):
+        self.assertAlmostEqual(veintiuna.risk_of_ruin(-1.0, 100.0, 1000.0), 1.0)
+        needed = veintiuna.bankroll_for(1.0, 100.0, 0.05)
+        self.assertGreater(needed, 0.0)
+        self.assertLess(veintiuna.risk_of_ruin(1.0, 100.0, needed), 0.051)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,158 @@
+use crate::betting::limit_bet;
+use crate::config::GameConfig;
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+/// Variance of one blackjack hand in squared units, used for Kelly sizing.
+const HAND_VARIANCE: f32 = 1.33;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvisorMode {
+    #[default]
+    Off,
+    /// fixed units per true count
+    Ramp,
+    /// fraction of the Kelly bet for the estimated advantage
+    Kelly,
+}
+
+impl AdvisorMode {
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "off" => Some(AdvisorMode::Off),
+            "ramp" => Some(AdvisorMode::Ramp),
+            "kelly" => Some(AdvisorMode::Kelly),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let key = match self {
+            AdvisorMode::Off => "off",
+            AdvisorMode::Ramp => "ramp",
+            AdvisorMode::Kelly => "kelly",
+        };
+        t!(&format!("advisor_mode.{}", key))
+    }
+}
+
+/// Count-based bet sizing (`[bet_advisor]` in game_config.toml).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BetAdvisorConfig {
+    pub mode: AdvisorMode,
+    /// units added for each true count above +1
+    pub units_per_count: f32,
+    /// largest bet of the ramp, in units
+    pub max_units: u32,
+    pub kelly_fraction: f32,
+    /// player advantage at true count 0
+    pub base_edge: f32,
+    /// advantage gained per true count
+    pub edge_per_count: f32,
+}
+
+impl Default for BetAdvisorConfig {
+    fn default() -> Self {
+        Self {
+            mode: AdvisorMode::Off,
+            units_per_count: 2.0,
+            max_units: 12,
+            kelly_fraction: 0.5,
+            base_edge: -0.005,
+            edge_per_count: 0.005,
+        }
+    }
+}
+
+impl BetAdvisorConfig {
+    /// Estimated player advantage at `true_count`.
+    pub fn edge(&self, true_count: f32) -> f32 {
+        self.base_edge + self.edge_per_count * true_count
+    }
+}
+
+/// Recommended bet for the current true count, or `None` when the advisor is off.
+pub fn advised_bet(cfg: &GameConfig, mode: AdvisorMode, true_count: f32, bankroll: u32) -> Option<u32> {
+    let adv = &cfg.bet_advisor;
+    let amount = match mode {
+        AdvisorMode::Off => return None,
+        AdvisorMode::Ramp => {
+            let units = ((true_count.floor() - 1.0) * adv.units_per_count).clamp(1.0, adv.max_units.max(1) as f32);
+            units * cfg.bet_amount as f32
+        }
+        AdvisorMode::Kelly => {
+            let edge = adv.edge(true_count);
+            if edge <= 0.0 {
+                0.0
+            } else {
+                bankroll as f32 * adv.kelly_fraction * edge / HAND_VARIANCE
+            }
+        }
+    };
+    Some(limit_bet(amount.round() as u32, cfg, bankroll))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> GameConfig {
+        let mut cfg = GameConfig { bet_amount: 10, table_min_bet: 5, table_max_bet: 1000, ..GameConfig::default() };
+        cfg.bet_advisor = BetAdvisorConfig {
+            units_per_count: 2.0,
+            max_units: 12,
+            kelly_fraction: 0.5,
+            base_edge: -0.005,
+            edge_per_count: 0.005,
+            ..BetAdvisorConfig::default()
+        };
+        cfg
+    }
+
+    #[test]
+    fn off_advises_nothing() {
+        assert_eq!(advised_bet(&config(), AdvisorMode::Off, 5.0, 10_000), None);
+    }
+
+    #[test]
+    fn ramp_adds_units_per_count_above_one() {
+        let cfg = config();
+        let ramp = |tc: f32| advised_bet(&cfg, AdvisorMode::Ramp, tc, 10_000).unwrap();
+        assert_eq!(ramp(-3.0), 10);
+        assert_eq!(ramp(0.0), 10);
+        assert_eq!(ramp(2.0), 20);
+        // a partial count does not reach the next step
+        assert_eq!(ramp(2.9), 20);
+        assert_eq!(ramp(4.0), 60);
+        assert_eq!(ramp(10.0), 120);
+    }
+
+    #[test]
+    fn ramp_stays_within_bankroll() {
+        assert_eq!(advised_bet(&config(), AdvisorMode::Ramp, 4.0, 30), Some(30));
+    }
+
+    #[test]
+    fn kelly_bets_a_fraction_of_the_edge_over_the_variance() {
+        let cfg = config();
+        let kelly = |tc: f32, bank: u32| advised_bet(&cfg, AdvisorMode::Kelly, tc, bank).unwrap();
+        // 10000 × 0.5 × 1% / 1.33
+        assert_eq!(kelly(3.0, 10_000), 38);
+        assert_eq!(kelly(3.0, 20_000), 75);
+        assert_eq!(kelly(21.0, 10_000), 376);
+    }
+
+    #[test]
+    fn kelly_without_an_edge_bets_the_table_minimum() {
+        let cfg = config();
+        assert_eq!(advised_bet(&cfg, AdvisorMode::Kelly, 1.0, 10_000), Some(5));
+        assert_eq!(advised_bet(&cfg, AdvisorMode::Kelly, -4.0, 10_000), Some(5));
+    }
+
+    #[test]
+    fn kelly_is_capped_by_the_table_maximum() {
+        assert_eq!(advised_bet(&config(), AdvisorMode::Kelly, 100.0, 10_000), Some(1000));
+    }
+}
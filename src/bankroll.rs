@@ -0,0 +1,215 @@
+use crate::advisor::AdvisorMode;
+use crate::config::GameConfig;
+use crate::simulation::{SimOptions, simulate_spread};
+use crate::t;
+
+/// Per-hand expectation and volatility, in currency.
+#[derive(Debug, Clone, Copy)]
+pub struct GameStats {
+    pub win_rate: f64,
+    pub std_dev: f64,
+}
+
+impl GameStats {
+    /// From figures quoted per 100 hands, as the simulator reports them.
+    pub fn per_100(win_rate: f64, std_dev: f64) -> Self {
+        Self { win_rate: win_rate / 100.0, std_dev: std_dev / 10.0 }
+    }
+
+    /// Risk of ruin over an unlimited number of hands.
+    pub fn risk_of_ruin(&self, bankroll: f64) -> f64 {
+        if self.win_rate <= 0.0 {
+            return 1.0;
+        }
+        (-2.0 * self.win_rate * bankroll / (self.std_dev * self.std_dev)).exp().min(1.0)
+    }
+
+    /// Hands until the expected win equals one standard deviation.
+    pub fn n0(&self) -> Option<f64> {
+        (self.win_rate > 0.0).then(|| (self.std_dev / self.win_rate).powi(2))
+    }
+
+    /// Bankroll whose risk of ruin equals `target` (0–1).
+    pub fn bankroll_for(&self, target: f64) -> Option<f64> {
+        (self.win_rate > 0.0 && target > 0.0 && target < 1.0)
+            .then(|| -(self.std_dev * self.std_dev) * target.ln() / (2.0 * self.win_rate))
+    }
+}
+
+pub struct BankrollQuery {
+    pub bankroll: u32,
+    /// target risk of ruin, 0–1
+    pub target_ror: f64,
+    pub mode: AdvisorMode,
+    /// win rate and standard deviation per 100 hands; simulated when absent
+    pub given: Option<(f64, f64)>,
+    pub sim: SimOptions,
+}
+
+/// Prints the analytic figures, backed by simulation of the configured game.
+pub fn run_analysis(cfg: &GameConfig, q: &BankrollQuery) {
+    let cur = &cfg.currency_name;
+    let mut game = cfg.clone();
+    game.player_starting_bank = q.bankroll;
+
+    println!("{}", t!("bankroll.title"));
+    print_game(cfg, q.mode);
+
+    let (stats, source) = match q.given {
+        Some((wr, sd)) => (GameStats::per_100(wr, sd), t!("bankroll.given")),
+        None => {
+            // long sessions from an effectively unlimited bankroll measure the game itself
+            let mut probe = game.clone();
+            if q.mode != AdvisorMode::Kelly {
+                probe.player_starting_bank = u32::MAX / 2;
+            }
+            let report = simulate_spread(&probe, q.mode, &q.sim);
+            (GameStats::per_100(report.win_rate, report.std_dev), t!("bankroll.simulated"))
+        }
+    };
+    println!(
+        "{}",
+        t!(
+            "bankroll.stats",
+            win_rate = format!("{:+.2}", stats.win_rate * 100.0),
+            std_dev = format!("{:.1}", stats.std_dev * 10.0),
+            currency = cur,
+            source = source
+        )
+    );
+    println!(
+        "{}",
+        t!(
+            "bankroll.target",
+            bank = q.bankroll,
+            currency = cur,
+            percent = format!("{:.1}", q.target_ror * 100.0)
+        )
+    );
+
+    println!("\n{}", t!("bankroll.theory"));
+    println!(
+        "{}",
+        t!("bankroll.risk", percent = format!("{:.2}", stats.risk_of_ruin(f64::from(q.bankroll)) * 100.0))
+    );
+    let required = stats.bankroll_for(q.target_ror);
+    match (stats.n0(), required) {
+        (Some(n0), Some(required)) => {
+            println!("{}", t!("bankroll.n0", hands = format!("{:.0}", n0)));
+            println!("{}", t!("bankroll.required", bank = format!("{:.0}", required), currency = cur));
+        }
+        _ => println!("{}", t!("bankroll.negative_edge")),
+    }
+
+    println!("\n{}", t!("bankroll.simulation", sessions = q.sim.sessions, hands = q.sim.rounds));
+    let report = simulate_spread(&game, q.mode, &q.sim);
+    println!(
+        "{}",
+        t!(
+            "bankroll.simulated_risk",
+            bank = q.bankroll,
+            currency = cur,
+            percent = format!("{:.2}", report.ruin_rate * 100.0)
+        )
+    );
+    if let Some(required) = required.filter(|r| *r < f64::from(u32::MAX)) {
+        game.player_starting_bank = required.ceil() as u32;
+        let check = simulate_spread(&game, q.mode, &q.sim);
+        println!(
+            "{}",
+            t!(
+                "bankroll.required_risk",
+                bank = game.player_starting_bank,
+                currency = cur,
+                percent = format!("{:.2}", check.ruin_rate * 100.0)
+            )
+        );
+    }
+    println!("\n{}", t!("bankroll.note"));
+}
+
+fn print_game(cfg: &GameConfig, mode: AdvisorMode) {
+    let adv = &cfg.bet_advisor;
+    let cur = &cfg.currency_name;
+    println!(
+        "{}",
+        t!(
+            "bankroll.rules",
+            rules = cfg.rules.summary(),
+            penetration = format!("{:.0}", cfg.rules.penetration * 100.0),
+            edge = format!("{:.2}", cfg.rules.house_edge())
+        )
+    );
+    match mode {
+        AdvisorMode::Off => println!("{}", t!("bankroll.flat", bet = cfg.bet_amount, currency = cur)),
+        AdvisorMode::Ramp => println!(
+            "{}",
+            t!(
+                "bankroll.ramp",
+                max = adv.max_units,
+                units = adv.units_per_count,
+                unit = cfg.bet_amount,
+                currency = cur
+            )
+        ),
+        AdvisorMode::Kelly => println!("{}", t!("bankroll.kelly", fraction = adv.kelly_fraction)),
+    }
+    println!("{}", t!("bankroll.strategy", plays = cfg.deviations.plays.len()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn per_100_figures_scale_to_one_hand() {
+        let stats = GameStats::per_100(1.0, 115.0);
+        assert!(close(stats.win_rate, 0.01));
+        assert!(close(stats.std_dev, 11.5));
+    }
+
+    #[test]
+    fn risk_of_ruin_follows_the_exponential_formula() {
+        let stats = GameStats::per_100(1.0, 115.0);
+        assert!(close(stats.risk_of_ruin(0.0), 1.0));
+        assert!(close(stats.risk_of_ruin(1000.0), (-2.0 * 0.01 * 1000.0 / 132.25f64).exp()));
+        assert!(stats.risk_of_ruin(20000.0) < stats.risk_of_ruin(10000.0));
+    }
+
+    #[test]
+    fn n0_is_variance_over_squared_win_rate() {
+        let stats = GameStats::per_100(1.0, 115.0);
+        assert!(close(stats.n0().unwrap(), 1_322_500.0));
+    }
+
+    #[test]
+    fn required_bankroll_gives_the_target_risk() {
+        let stats = GameStats::per_100(1.5, 110.0);
+        for target in [0.01, 0.05, 0.135] {
+            let bankroll = stats.bankroll_for(target).unwrap();
+            assert!(close(stats.risk_of_ruin(bankroll), target));
+        }
+        assert!(close(GameStats::per_100(1.0, 115.0).bankroll_for(0.05).unwrap(), -132.25 * 0.05f64.ln() / 0.02));
+    }
+
+    #[test]
+    fn no_edge_means_certain_ruin() {
+        for win_rate in [0.0, -2.0] {
+            let stats = GameStats::per_100(win_rate, 115.0);
+            assert!(close(stats.risk_of_ruin(1_000_000.0), 1.0));
+            assert!(stats.n0().is_none());
+            assert!(stats.bankroll_for(0.05).is_none());
+        }
+    }
+
+    #[test]
+    fn target_must_be_strictly_between_zero_and_one() {
+        let stats = GameStats::per_100(1.0, 115.0);
+        assert!(stats.bankroll_for(0.0).is_none());
+        assert!(stats.bankroll_for(1.0).is_none());
+    }
+}
@@ -0,0 +1,487 @@
+use crate::config::GameConfig;
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+/// Result of a finished round as seen by a betting progression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundResult {
+    Win,
+    Loss,
+    Push,
+}
+
+impl RoundResult {
+    pub fn from_net(net: i64) -> Self {
+        match net {
+            n if n > 0 => RoundResult::Win,
+            n if n < 0 => RoundResult::Loss,
+            _ => RoundResult::Push,
+        }
+    }
+}
+
+/// A betting system that sets the next wager from previous results.
+pub trait BetProgression {
+    /// Next wager in multiples of the base unit, before limits are applied.
+    fn units(&self) -> u32;
+    fn record(&mut self, result: RoundResult);
+    fn reset(&mut self);
+    /// Where the progression stands, to be saved between launches.
+    fn state(&self) -> Vec<i64>;
+    /// Picks up from a saved `state`; anything it cannot use starts over.
+    fn restore(&mut self, state: &[i64]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BettingSystem {
+    #[default]
+    Flat,
+    Martingale,
+    Paroli,
+    Fibonacci,
+    #[serde(alias = "d_alembert")]
+    Dalembert,
+    OscarsGrind,
+    #[serde(alias = "1-3-2-6")]
+    OneThreeTwoSix,
+    Labouchere,
+}
+
+impl BettingSystem {
+    pub const ALL: [BettingSystem; 8] = [
+        BettingSystem::Flat,
+        BettingSystem::Martingale,
+        BettingSystem::Paroli,
+        BettingSystem::Fibonacci,
+        BettingSystem::Dalembert,
+        BettingSystem::OscarsGrind,
+        BettingSystem::OneThreeTwoSix,
+        BettingSystem::Labouchere,
+    ];
+
+    /// Name used in `game_config.toml` and on the command line.
+    pub fn key(&self) -> &'static str {
+        match self {
+            BettingSystem::Flat => "flat",
+            BettingSystem::Martingale => "martingale",
+            BettingSystem::Paroli => "paroli",
+            BettingSystem::Fibonacci => "fibonacci",
+            BettingSystem::Dalembert => "dalembert",
+            BettingSystem::OscarsGrind => "oscars_grind",
+            BettingSystem::OneThreeTwoSix => "one_three_two_six",
+            BettingSystem::Labouchere => "labouchere",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    pub fn label(&self) -> String {
+        t!(&format!("betting_system.{}", self.key()))
+    }
+
+    pub fn build(&self) -> Box<dyn BetProgression> {
+        match self {
+            BettingSystem::Flat => Box::new(Flat),
+            BettingSystem::Martingale => Box::new(Martingale::default()),
+            BettingSystem::Paroli => Box::new(Paroli::default()),
+            BettingSystem::Fibonacci => Box::new(Fibonacci::default()),
+            BettingSystem::Dalembert => Box::new(Dalembert::default()),
+            BettingSystem::OscarsGrind => Box::new(OscarsGrind::default()),
+            BettingSystem::OneThreeTwoSix => Box::new(OneThreeTwoSix::default()),
+            BettingSystem::Labouchere => Box::new(Labouchere::default()),
+        }
+    }
+}
+
+/// Turns a progression's units into a wager within table limits and bankroll.
+pub fn capped_bet(units: u32, unit: u32, cfg: &GameConfig, bankroll: u32) -> u32 {
+    limit_bet(units.saturating_mul(unit), cfg, bankroll)
+}
+
+pub fn limit_bet(amount: u32, cfg: &GameConfig, bankroll: u32) -> u32 {
+    // not `clamp`: it panics on a config with the minimum above the maximum
+    amount.max(cfg.table_min_bet).min(cfg.table_max_bet).min(bankroll)
+}
+
+/// A saved count of units, at least one.
+fn unit_count(value: Option<&i64>) -> Option<u32> {
+    value.and_then(|&n| u32::try_from(n).ok()).filter(|&n| n >= 1)
+}
+
+struct Flat;
+
+impl BetProgression for Flat {
+    fn units(&self) -> u32 {
+        1
+    }
+    fn record(&mut self, _result: RoundResult) {}
+    fn reset(&mut self) {}
+    fn state(&self) -> Vec<i64> {
+        Vec::new()
+    }
+    fn restore(&mut self, _state: &[i64]) {}
+}
+
+/// Double after every loss, back to one unit after a win.
+struct Martingale {
+    units: u32,
+}
+
+impl Default for Martingale {
+    fn default() -> Self {
+        Self { units: 1 }
+    }
+}
+
+impl BetProgression for Martingale {
+    fn units(&self) -> u32 {
+        self.units
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => self.units = 1,
+            RoundResult::Loss => self.units = self.units.saturating_mul(2),
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        vec![i64::from(self.units)]
+    }
+    fn restore(&mut self, state: &[i64]) {
+        self.units = unit_count(state.first()).unwrap_or(1);
+    }
+}
+
+/// Double after a win for up to three wins in a row.
+#[derive(Default)]
+struct Paroli {
+    streak: u32,
+}
+
+impl BetProgression for Paroli {
+    fn units(&self) -> u32 {
+        1 << self.streak
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => self.streak = (self.streak + 1) % 3,
+            RoundResult::Loss => self.streak = 0,
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        vec![i64::from(self.streak)]
+    }
+    fn restore(&mut self, state: &[i64]) {
+        self.streak = state.first().and_then(|&n| u32::try_from(n).ok()).filter(|&n| n < 3).unwrap_or(0);
+    }
+}
+
+/// One step up the Fibonacci sequence per loss, two steps back per win.
+#[derive(Default)]
+struct Fibonacci {
+    step: usize,
+}
+
+impl Fibonacci {
+    /// The first step whose bet no longer fits in a `u32`; it stays there.
+    const MAX_STEP: usize = 47;
+}
+
+impl BetProgression for Fibonacci {
+    fn units(&self) -> u32 {
+        let (mut a, mut b) = (1u32, 1u32);
+        for _ in 0..self.step {
+            (a, b) = (b, a.saturating_add(b));
+        }
+        a
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => self.step = self.step.saturating_sub(2),
+            RoundResult::Loss => self.step = (self.step + 1).min(Self::MAX_STEP),
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        vec![self.step as i64]
+    }
+    fn restore(&mut self, state: &[i64]) {
+        self.step = state.first().and_then(|&n| usize::try_from(n).ok()).map_or(0, |n| n.min(Self::MAX_STEP));
+    }
+}
+
+/// One unit up after a loss, one unit down after a win.
+struct Dalembert {
+    units: u32,
+}
+
+impl Default for Dalembert {
+    fn default() -> Self {
+        Self { units: 1 }
+    }
+}
+
+impl BetProgression for Dalembert {
+    fn units(&self) -> u32 {
+        self.units
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => self.units = self.units.saturating_sub(1).max(1),
+            RoundResult::Loss => self.units = self.units.saturating_add(1),
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        vec![i64::from(self.units)]
+    }
+    fn restore(&mut self, state: &[i64]) {
+        self.units = unit_count(state.first()).unwrap_or(1);
+    }
+}
+
+/// Aims for one unit of profit per cycle, raising the bet only after wins.
+struct OscarsGrind {
+    units: u32,
+    profit: i64,
+}
+
+impl Default for OscarsGrind {
+    fn default() -> Self {
+        Self { units: 1, profit: 0 }
+    }
+}
+
+impl BetProgression for OscarsGrind {
+    fn units(&self) -> u32 {
+        self.units
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => {
+                self.profit = self.profit.saturating_add(i64::from(self.units));
+                if self.profit >= 1 {
+                    self.reset();
+                } else {
+                    // never bet more than needed to finish the cycle
+                    let needed = u32::try_from(1i64.saturating_sub(self.profit)).unwrap_or(u32::MAX);
+                    self.units = self.units.saturating_add(1).min(needed);
+                }
+            }
+            RoundResult::Loss => self.profit = self.profit.saturating_sub(i64::from(self.units)),
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        vec![i64::from(self.units), self.profit]
+    }
+    fn restore(&mut self, state: &[i64]) {
+        match (unit_count(state.first()), state.get(1)) {
+            (Some(units), Some(&profit)) if profit < 1 => *self = Self { units, profit },
+            _ => self.reset(),
+        }
+    }
+}
+
+/// Walks the 1-3-2-6 sequence on wins and restarts on a loss.
+#[derive(Default)]
+struct OneThreeTwoSix {
+    step: usize,
+}
+
+impl BetProgression for OneThreeTwoSix {
+    fn units(&self) -> u32 {
+        [1, 3, 2, 6][self.step]
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => self.step = (self.step + 1) % 4,
+            RoundResult::Loss => self.step = 0,
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        vec![self.step as i64]
+    }
+    fn restore(&mut self, state: &[i64]) {
+        self.step = state.first().and_then(|&n| usize::try_from(n).ok()).filter(|&n| n < 4).unwrap_or(0);
+    }
+}
+
+/// Cancellation system: bet first + last, cross them off on a win, append the bet on a loss.
+struct Labouchere {
+    line: Vec<u32>,
+}
+
+impl Default for Labouchere {
+    fn default() -> Self {
+        Self { line: vec![1, 2, 3, 4] }
+    }
+}
+
+impl BetProgression for Labouchere {
+    fn units(&self) -> u32 {
+        match self.line.as_slice() {
+            [] => 1,
+            [only] => *only,
+            [first, .., last] => first.saturating_add(*last),
+        }
+    }
+    fn record(&mut self, result: RoundResult) {
+        match result {
+            RoundResult::Win => {
+                self.line.pop();
+                if !self.line.is_empty() {
+                    self.line.remove(0);
+                }
+                if self.line.is_empty() {
+                    self.reset();
+                }
+            }
+            RoundResult::Loss => {
+                let units = self.units();
+                self.line.push(units);
+            }
+            RoundResult::Push => {}
+        }
+    }
+    fn reset(&mut self) {
+        *self = Self::default();
+    }
+    fn state(&self) -> Vec<i64> {
+        self.line.iter().map(|&units| i64::from(units)).collect()
+    }
+    fn restore(&mut self, state: &[i64]) {
+        let line: Option<Vec<u32>> = state.iter().map(|&n| unit_count(Some(&n))).collect();
+        match line {
+            Some(line) if !line.is_empty() => self.line = line,
+            _ => self.reset(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use RoundResult::*;
+
+    const SCRIPT: [RoundResult; 8] = [Loss, Loss, Win, Push, Loss, Win, Win, Loss];
+
+    /// Units bet before each round of `SCRIPT`, then after the last one.
+    fn units_over_script(system: BettingSystem) -> Vec<u32> {
+        let mut progression = system.build();
+        let mut units = Vec::new();
+        for result in SCRIPT {
+            units.push(progression.units());
+            progression.record(result);
+        }
+        units.push(progression.units());
+        units
+    }
+
+    #[test]
+    fn each_system_follows_its_sequence() {
+        use BettingSystem::*;
+        let expected: [(BettingSystem, [u32; 9]); 8] = [
+            (Flat, [1, 1, 1, 1, 1, 1, 1, 1, 1]),
+            (Martingale, [1, 2, 4, 1, 1, 2, 1, 1, 2]),
+            (Paroli, [1, 1, 1, 2, 2, 1, 2, 4, 1]),
+            (Fibonacci, [1, 1, 2, 1, 1, 1, 1, 1, 1]),
+            (Dalembert, [1, 2, 3, 2, 2, 3, 2, 1, 2]),
+            (OscarsGrind, [1, 1, 1, 2, 2, 2, 2, 1, 1]),
+            (OneThreeTwoSix, [1, 1, 1, 3, 3, 1, 3, 2, 1]),
+            (Labouchere, [5, 6, 7, 7, 7, 9, 8, 4, 8]),
+        ];
+        for (system, units) in expected {
+            assert_eq!(units_over_script(system), units, "{}", system.key());
+        }
+    }
+
+    #[test]
+    fn state_round_trips() {
+        for system in BettingSystem::ALL {
+            let mut played = system.build();
+            for result in SCRIPT {
+                played.record(result);
+            }
+            let mut restored = system.build();
+            restored.restore(&played.state());
+            assert_eq!(restored.state(), played.state(), "{}", system.key());
+            assert_eq!(restored.units(), played.units(), "{}", system.key());
+        }
+    }
+
+    #[test]
+    fn unusable_state_starts_over() {
+        let labouchere_line = [-1, 2, 3];
+        for state in [&[][..], &[-3][..], &[-1, -1][..], &labouchere_line[..]] {
+            for system in BettingSystem::ALL {
+                let mut restored = system.build();
+                restored.restore(state);
+                assert_eq!(restored.state(), system.build().state(), "{} from {:?}", system.key(), state);
+            }
+        }
+        let mut paroli = BettingSystem::Paroli.build();
+        paroli.restore(&[3]);
+        assert_eq!(paroli.units(), 1);
+        let mut oscars = BettingSystem::OscarsGrind.build();
+        oscars.restore(&[2, 1]);
+        assert_eq!(oscars.state(), [1, 0]);
+    }
+
+    #[test]
+    fn oversized_state_saturates() {
+        let big = i64::from(u32::MAX);
+        let mut fibonacci = BettingSystem::Fibonacci.build();
+        fibonacci.restore(&[i64::MAX]);
+        assert_eq!(fibonacci.state(), [Fibonacci::MAX_STEP as i64]);
+        assert_eq!(fibonacci.units(), u32::MAX);
+        fibonacci.record(Loss);
+        assert_eq!(fibonacci.state(), [Fibonacci::MAX_STEP as i64]);
+
+        let mut labouchere = BettingSystem::Labouchere.build();
+        labouchere.restore(&[big, big]);
+        assert_eq!(labouchere.units(), u32::MAX);
+        labouchere.record(Loss);
+        labouchere.restore(&[i64::MAX]);
+        assert_eq!(labouchere.state(), [1, 2, 3, 4]);
+
+        let mut dalembert = BettingSystem::Dalembert.build();
+        dalembert.restore(&[big]);
+        dalembert.record(Loss);
+        assert_eq!(dalembert.units(), u32::MAX);
+
+        let mut martingale = BettingSystem::Martingale.build();
+        martingale.restore(&[big]);
+        martingale.record(Loss);
+        assert_eq!(martingale.units(), u32::MAX);
+
+        let mut oscars = BettingSystem::OscarsGrind.build();
+        oscars.restore(&[big, i64::MIN]);
+        oscars.record(Loss);
+        oscars.record(Win);
+        assert_eq!(oscars.units(), u32::MAX);
+    }
+}
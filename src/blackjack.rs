@@ -1,90 +1,314 @@
-use crate::card::{Card, hand_value, print_hand};
-use crate::game_action::{GameAction, PlayerActionResult, can_double_down, can_split, can_surrender};
-use dialoguer::{Select, theme::ColorfulTheme};
+use crate::bots::Controller;
+use crate::card::{Card, hand_value, is_blackjack, print_hand};
+#[cfg(feature = "cli")]
+use crate::config::InputMode;
+use crate::config::GameConfig;
+use crate::game_action::GameAction;
+use crate::round::Round;
+use crate::rules::BlackjackPayout;
+use crate::shoe::Shoe;
+use crate::strategy::is_soft;
+use crate::t;
+#[cfg(feature = "cli")]
+use crate::strategy::basic_strategy;
+#[cfg(feature = "cli")]
+use crate::prompt;
+#[cfg(feature = "cli")]
+use dialoguer::console::Term;
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Duration;
 
-pub fn player_turn(deck: &mut Vec<Card>, hand: &mut Vec<Card>, is_first_turn: bool) -> anyhow::Result<PlayerActionResult> {
-    loop {
-        print_hand("プレイヤー", hand, false);
-        if hand_value(hand) >= 21 {
-            return Ok(PlayerActionResult::Stand);
+/// Called after every action with the round and the shoe, so the round in
+/// progress can be saved.
+pub type Checkpoint<'a> = dyn FnMut(&Round, &Shoe) -> anyhow::Result<()> + 'a;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandOutcome {
+    Bust,
+    DealerBust,
+    Blackjack,
+    DealerBlackjack,
+    BothBlackjack,
+    Win,
+    EdgeWin,
+    Lose,
+    Push,
+    Surrender,
+}
+
+impl HandOutcome {
+    /// Catalog key; the same name the protocols serialize.
+    pub fn key(&self) -> &'static str {
+        match self {
+            HandOutcome::Bust => "bust",
+            HandOutcome::DealerBust => "dealer_bust",
+            HandOutcome::Blackjack => "blackjack",
+            HandOutcome::DealerBlackjack => "dealer_blackjack",
+            HandOutcome::BothBlackjack => "both_blackjack",
+            HandOutcome::Win => "win",
+            HandOutcome::EdgeWin => "edge_win",
+            HandOutcome::Lose => "lose",
+            HandOutcome::Push => "push",
+            HandOutcome::Surrender => "surrender",
         }
-        
-        let can_double = is_first_turn && can_double_down(hand);
-        let can_split_hand = is_first_turn && can_split(hand);
-        let can_surrender_hand = is_first_turn && can_surrender(hand);
-        
-        let valid_actions = GameAction::get_valid_actions(can_double, can_split_hand, can_surrender_hand);
-        let menu_items = GameAction::menu_items(can_double, can_split_hand, can_surrender_hand);
-        
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("次のアクションを選択してください:")
-            .default(0)
-            .items(&menu_items)
-            .interact()?;
-
-        if let Some(action) = valid_actions.get(selection) {
-            match action {
-                GameAction::Hit => {
-                    if let Some(card) = deck.pop() {
-                        hand.push(card);
-                        println!("カードを引きました: {}", card.short());
-                        // Continue the loop
-                    }
-                }
-                GameAction::Stand => {
-                    println!("スタンドしました");
-                    return Ok(PlayerActionResult::Stand);
-                }
-                GameAction::DoubleDown => {
-                    if let Some(card) = deck.pop() {
-                        hand.push(card);
-                        println!("ダブルダウン: カードを1枚引きました: {}", card.short());
-                        return Ok(PlayerActionResult::DoubleDown);
-                    } else {
-                        println!("デッキが不足しています");
-                        return Ok(PlayerActionResult::Stand);
-                    }
-                }
-                GameAction::Split => {
-                    if hand.len() == 2 && deck.len() >= 2 {
-                        let second_card = hand.pop().unwrap();
-                        let first_card = hand[0];
-                        
-                        // Create two new hands
-                        let mut hand1 = vec![first_card];
-                        let mut hand2 = vec![second_card];
-                        
-                        // Deal one card to each hand
-                        if let Some(card) = deck.pop() {
-                            hand1.push(card);
-                        }
-                        if let Some(card) = deck.pop() {
-                            hand2.push(card);
-                        }
-                        
-                        println!("スプリットしました");
-                        return Ok(PlayerActionResult::Split(hand1, hand2));
-                    } else {
-                        println!("スプリットできません");
-                    }
-                }
-                GameAction::Surrender => {
-                    println!("サレンダーしました（半額返却）");
-                    return Ok(PlayerActionResult::Surrender);
+    }
+
+    pub fn label(&self) -> String {
+        t!(&format!("outcome.{}", self.key()))
+    }
+
+    /// Net change to the bankroll for a hand that wagered `bet`.
+    pub fn payout(&self, bet: u32, blackjack: BlackjackPayout) -> i64 {
+        let bet = i64::from(bet);
+        match self {
+            HandOutcome::Blackjack => blackjack.pay(bet),
+            HandOutcome::DealerBust
+            | HandOutcome::Win
+            | HandOutcome::EdgeWin => bet,
+            HandOutcome::Bust | HandOutcome::DealerBlackjack | HandOutcome::Lose => -bet,
+            HandOutcome::BothBlackjack | HandOutcome::Push => 0,
+            HandOutcome::Surrender => -(bet - bet / 2),
+        }
+    }
+}
+
+pub fn settle_hand(hand: &[Card], player_has_bj: bool, dealer_hand: &[Card], player_edge: f32) -> HandOutcome {
+    let pv = hand_value(hand);
+    let dv = hand_value(dealer_hand);
+    let dealer_has_bj = is_blackjack(dealer_hand);
+
+    if pv > 21 {
+        HandOutcome::Bust
+    } else if dv > 21 {
+        HandOutcome::DealerBust
+    } else if player_has_bj && !dealer_has_bj {
+        HandOutcome::Blackjack
+    } else if !player_has_bj && dealer_has_bj {
+        HandOutcome::DealerBlackjack
+    } else if player_has_bj && dealer_has_bj {
+        HandOutcome::BothBlackjack
+    } else if pv > dv {
+        HandOutcome::Win
+    } else if pv < dv {
+        HandOutcome::Lose
+    } else if player_edge > 0.0 {
+        // tie broken by player_edge bias
+        HandOutcome::EdgeWin
+    } else {
+        HandOutcome::Push
+    }
+}
+
+/// Prompts for actions on the active hand until play moves to the next hand.
+/// `seat` names whose turn it is when several players share the table.
+#[cfg(feature = "cli")]
+pub fn player_turn(
+    cfg: &GameConfig,
+    shoe: &mut Shoe,
+    round: &mut Round,
+    seat: Option<&str>,
+    checkpoint: &mut Checkpoint,
+) -> anyhow::Result<()> {
+    let Some((spot, index)) = round.active() else {
+        return Ok(());
+    };
+    let name = seat.map_or_else(|| t!("game.player"), str::to_string);
+    let prompt = match seat {
+        Some(name) => t!("play.seat_prompt", name = name),
+        None => t!("play.prompt"),
+    };
+
+    while round.active() == Some((spot, index)) {
+        print_hand(&name, &round.spots[spot].hands[index].cards, false);
+
+        let valid_actions = round.legal_actions();
+        if cfg.show_hints {
+            print_hint(cfg, round, &valid_actions, shoe.true_count());
+        }
+        let action = match cfg.input_mode {
+            InputMode::Menu => {
+                let selection = prompt::select(&prompt, &GameAction::menu_items(&valid_actions), 0)?;
+                valid_actions[selection]
+            }
+            InputMode::Keys => read_action_key(&prompt, &valid_actions)?,
+        };
+        round.apply(shoe, action)?;
+        checkpoint(round, shoe)?;
+
+        let hand = &round.spots[spot].hands[index].cards;
+        match action {
+            GameAction::Hit => {
+                println!("{}", t!("play.hit", card = hand[hand.len() - 1].announce()));
+                if round.active() != Some((spot, index)) {
+                    print_hand(&name, hand, false);
                 }
             }
-        } else {
-            println!("無効な選択です");
+            GameAction::Stand => println!("{}", t!("play.stand")),
+            GameAction::DoubleDown => println!("{}", t!("play.double", card = hand[hand.len() - 1].announce())),
+            GameAction::Split => println!("{}", t!("play.split")),
+            GameAction::Surrender => println!("{}", t!("play.surrender")),
         }
     }
+    Ok(())
 }
 
-pub fn dealer_turn(deck: &mut Vec<Card>, hand: &mut Vec<Card>) {
-    while hand_value(hand) < 17 {
-        if let Some(card) = deck.pop() {
-            hand.push(card);
-        } else {
-            break;
+/// Waits for one of h/s/d/p/r, refusing keys for actions the hand cannot take.
+#[cfg(feature = "cli")]
+fn read_action_key(prompt: &str, legal: &[GameAction]) -> anyhow::Result<GameAction> {
+    let keys: Vec<String> = legal.iter().map(|a| format!("{} {}", a.shortcut(), a.name())).collect();
+    println!("{} [{}]", prompt, keys.join(" / "));
+    let term = Term::stdout();
+    loop {
+        let key = term.read_char()?;
+        if key == '\u{3}' {
+            // raw mode swallows Ctrl+C; report it like the menus do
+            return Err(std::io::Error::from(std::io::ErrorKind::Interrupted).into());
+        }
+        match GameAction::from_shortcut(key) {
+            Some(action) if legal.contains(&action) => return Ok(action),
+            Some(action) => println!("{}", t!("play.key_not_allowed", action = action.name(), keys = keys.join(" / "))),
+            None => println!("{}", t!("play.key_unknown", key = key.escape_default(), keys = keys.join(" / "))),
         }
     }
 }
+
+/// Recommended play, noting when an index play overrides basic strategy.
+#[cfg(feature = "cli")]
+fn print_hint(cfg: &GameConfig, round: &Round, legal: &[GameAction], true_count: f32) {
+    let Some(hand) = round.active_hand() else {
+        return;
+    };
+    let up = round.dealer_up_card();
+    match cfg.deviations.lookup(&hand.cards, up, legal, true_count) {
+        Some(play) => println!(
+            "{}",
+            t!(
+                "hint.index_play",
+                action = play.action.label(),
+                play = play.describe(),
+                tc = format!("{:+.1}", true_count)
+            )
+        ),
+        None => println!(
+            "{}",
+            t!(
+                "hint.basic",
+                action = basic_strategy(&hand.cards, up, legal).label(),
+                tc = format!("{:+.1}", true_count)
+            )
+        ),
+    }
+}
+
+/// Asks whether to insure against a dealer ace.
+#[cfg(feature = "cli")]
+pub fn insurance_turn(
+    cfg: &GameConfig,
+    shoe: &mut Shoe,
+    round: &mut Round,
+    seat: Option<&str>,
+    checkpoint: &mut Checkpoint,
+) -> anyhow::Result<()> {
+    let Some(spot) = round.pending_insurance() else {
+        return Ok(());
+    };
+    let cost = round.spots[spot].hands[0].bet / 2;
+    if cfg.show_hints {
+        let tc = shoe.true_count();
+        let advice = if cfg.deviations.take_insurance(tc) { t!("hint.insure") } else { t!("hint.no_insure") };
+        println!(
+            "{}",
+            t!(
+                "hint.insurance",
+                advice = advice,
+                tc = format!("{:+.1}", tc),
+                index = format!("{:+}", cfg.deviations.insurance_index)
+            )
+        );
+    }
+    let question = match seat {
+        Some(name) => t!("play.seat_insurance_prompt", name = name, cost = cost, currency = cfg.currency_name),
+        None => t!("play.insurance_prompt", cost = cost, currency = cfg.currency_name),
+    };
+    let selection = prompt::select(&question, &[t!("menu.yes"), t!("menu.no")], 1)?;
+    round.resolve_insurance(shoe, selection == 0);
+    checkpoint(round, shoe)
+}
+
+/// Plays every hand of the active seat for a bot, announcing each action.
+pub fn bot_turn(
+    cfg: &GameConfig,
+    shoe: &mut Shoe,
+    round: &mut Round,
+    name: &str,
+    bot: Controller,
+    checkpoint: &mut Checkpoint,
+) -> anyhow::Result<()> {
+    let Some((spot, _)) = round.active() else {
+        return Ok(());
+    };
+    let pause = Duration::from_millis(cfg.table.bot_delay_ms);
+    let mut shown = None;
+
+    while let Some((s, index)) = round.active()
+        && s == spot
+    {
+        let hand = &round.spots[spot].hands[index];
+        if shown != Some(index) {
+            let label = if round.spots[spot].hands.len() > 1 {
+                t!("game.named_hand", name = name, hand = index + 1)
+            } else {
+                name.to_string()
+            };
+            print_hand(&label, &hand.cards, false);
+            shown = Some(index);
+        }
+        let legal = round.legal_actions();
+        let action = bot.choose(&hand.cards, round.dealer_up_card(), &legal, shoe.true_count(), &cfg.deviations);
+        thread::sleep(pause);
+        round.apply(shoe, action)?;
+        checkpoint(round, shoe)?;
+
+        let cards = &round.spots[spot].hands[index].cards;
+        let total = hand_value(cards);
+        match action {
+            GameAction::Hit | GameAction::DoubleDown => {
+                let draw =
+                    t!("bot.draw", name = name, action = action.name(), card = cards[cards.len() - 1].announce(), total = total);
+                if total > 21 {
+                    println!("{}{}", draw, t!("bot.bust_suffix"));
+                } else {
+                    println!("{}", draw);
+                }
+            }
+            GameAction::Stand => println!("{}", t!("bot.stand", name = name, total = total)),
+            GameAction::Split | GameAction::Surrender => println!("{}", t!("bot.action", name = name, action = action.name())),
+        }
+    }
+    Ok(())
+}
+
+/// A bot answers the insurance offer for the pending seat.
+pub fn bot_insurance(
+    cfg: &GameConfig,
+    shoe: &mut Shoe,
+    round: &mut Round,
+    name: &str,
+    bot: Controller,
+    checkpoint: &mut Checkpoint,
+) -> anyhow::Result<()> {
+    let take = bot.takes_insurance(shoe.true_count(), &cfg.deviations);
+    thread::sleep(Duration::from_millis(cfg.table.bot_delay_ms));
+    println!("{}", if take { t!("bot.insured", name = name) } else { t!("bot.declined_insurance", name = name) });
+    round.resolve_insurance(shoe, take);
+    checkpoint(round, shoe)
+}
+
+/// Draws to 17; with `hits_soft_17` (H17) a soft 17 draws too.
+pub fn dealer_turn(shoe: &mut Shoe, hand: &mut Vec<Card>, hits_soft_17: bool) {
+    while hand_value(hand) < 17 || (hits_soft_17 && hand_value(hand) == 17 && is_soft(hand)) {
+        hand.push(shoe.draw());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::card::{Card, hand_value};
+use crate::deviations::{Deviations, count_strategy};
+use crate::game_action::GameAction;
+use crate::strategy::{basic_strategy, is_soft};
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+/// Who makes the decisions for a seat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Controller {
+    #[default]
+    Human,
+    /// basic strategy, flat bets
+    Basic,
+    /// Hi-Lo counter: index plays, insurance and a bet ramp
+    Counter,
+    /// never takes a card that could bust the hand
+    NeverBust,
+    /// copies the dealer: hits below 17
+    DealerMimic,
+    /// any legal action at random
+    Random,
+}
+
+impl Controller {
+    pub fn is_human(&self) -> bool {
+        *self == Controller::Human
+    }
+
+    pub fn label(&self) -> String {
+        let key = match self {
+            Controller::Human => "human",
+            Controller::Basic => "basic",
+            Controller::Counter => "counter",
+            Controller::NeverBust => "never_bust",
+            Controller::DealerMimic => "dealer_mimic",
+            Controller::Random => "random",
+        };
+        t!(&format!("controller.{}", key))
+    }
+
+    /// The bot's play for the active hand; `legal` is never empty.
+    pub fn choose(
+        &self,
+        hand: &[Card],
+        dealer_up: Card,
+        legal: &[GameAction],
+        true_count: f32,
+        deviations: &Deviations,
+    ) -> GameAction {
+        let total = hand_value(hand);
+        match self {
+            Controller::Human | Controller::Basic => basic_strategy(hand, dealer_up, legal),
+            Controller::Counter => count_strategy(hand, dealer_up, legal, true_count, deviations),
+            Controller::NeverBust => {
+                let safe = total <= 11 || (is_soft(hand) && total <= 17);
+                if safe { GameAction::Hit } else { GameAction::Stand }
+            }
+            Controller::DealerMimic => {
+                if total < 17 { GameAction::Hit } else { GameAction::Stand }
+            }
+            Controller::Random => legal[rand::random_range(..legal.len())],
+        }
+    }
+
+    /// Only the counter insures, and only at the insurance index.
+    pub fn takes_insurance(&self, true_count: f32, deviations: &Deviations) -> bool {
+        *self == Controller::Counter && deviations.take_insurance(true_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use crate::shoe::Shoe;
+    use GameAction::*;
+    use Rank::*;
+
+    fn hand(ranks: &[Rank]) -> Vec<Card> {
+        ranks.iter().map(|&rank| Card(rank, Suit::Spades)).collect()
+    }
+
+    fn up(rank: Rank) -> Card {
+        Card(rank, Suit::Hearts)
+    }
+
+    const ALL: &[GameAction] = &[Hit, Stand, DoubleDown, Split, Surrender];
+    const FIRST_TWO: &[GameAction] = &[Hit, Stand, DoubleDown, Surrender];
+    const NO_SURRENDER: &[GameAction] = &[Hit, Stand, DoubleDown];
+
+    fn choose(bot: Controller, cards: &[Card], dealer: Rank, legal: &[GameAction], true_count: f32) -> GameAction {
+        bot.choose(cards, up(dealer), legal, true_count, &Deviations::builtin())
+    }
+
+    /// A shoe dealt down from `seed` until the count is at least `target`.
+    fn hot_shoe(seed: u64, target: f32) -> Shoe {
+        let mut shoe = Shoe::new(6, 0.75, seed);
+        while shoe.true_count() < target {
+            assert!(!shoe.needs_shuffle(), "seed {} never reached TC {}", seed, target);
+            shoe.draw();
+        }
+        shoe
+    }
+
+    #[test]
+    fn basic_bot_plays_the_chart() {
+        let bot = Controller::Basic;
+        assert_eq!(choose(bot, &hand(&[Ten, Six]), Ten, FIRST_TWO, 0.0), Surrender);
+        assert_eq!(choose(bot, &hand(&[Ten, Six]), Ten, NO_SURRENDER, 0.0), Hit);
+        assert_eq!(choose(bot, &hand(&[Six, Five]), Six, FIRST_TWO, 0.0), DoubleDown);
+        assert_eq!(choose(bot, &hand(&[Eight, Eight]), Ten, ALL, 0.0), Split);
+        // the count changes nothing
+        assert_eq!(choose(bot, &hand(&[Ten, Two]), Three, NO_SURRENDER, 5.0), Hit);
+    }
+
+    #[test]
+    fn counter_bot_plays_the_index_plays() {
+        let bot = Controller::Counter;
+        let sixteen = hand(&[Ten, Six]);
+        for tc in [-1.0, 0.0, 5.0] {
+            assert_eq!(choose(bot, &sixteen, Ten, FIRST_TWO, tc), Surrender, "TC {}", tc);
+        }
+        assert_eq!(choose(bot, &sixteen, Ten, NO_SURRENDER, -1.0), Hit);
+        assert_eq!(choose(bot, &sixteen, Ten, NO_SURRENDER, 0.0), Stand);
+        assert_eq!(choose(bot, &hand(&[Ten, Two]), Three, NO_SURRENDER, 2.0), Stand);
+        assert_eq!(choose(bot, &hand(&[Ten, Four]), Ten, FIRST_TWO, 3.0), Surrender);
+    }
+
+    #[test]
+    fn counter_bot_follows_a_seeded_shoe() {
+        let shoe = hot_shoe(8, 4.0);
+        let fifteen = hand(&[Ten, Five]);
+        assert_eq!(choose(Controller::Counter, &fifteen, Ten, NO_SURRENDER, shoe.true_count()), Stand);
+        assert_eq!(choose(Controller::Basic, &fifteen, Ten, NO_SURRENDER, shoe.true_count()), Hit);
+        assert!(Controller::Counter.takes_insurance(shoe.true_count(), &Deviations::builtin()));
+        let fresh = Shoe::new(6, 0.75, 8);
+        assert_eq!(choose(Controller::Counter, &fifteen, Ten, NO_SURRENDER, fresh.true_count()), Hit);
+        assert!(!Controller::Counter.takes_insurance(fresh.true_count(), &Deviations::builtin()));
+    }
+
+    #[test]
+    fn only_the_counter_insures() {
+        let deviations = Deviations::builtin();
+        assert!(Controller::Counter.takes_insurance(3.0, &deviations));
+        assert!(!Controller::Counter.takes_insurance(2.9, &deviations));
+        let others = [Controller::Human, Controller::Basic, Controller::NeverBust, Controller::DealerMimic, Controller::Random];
+        for bot in others {
+            assert!(!bot.takes_insurance(10.0, &deviations), "{:?}", bot);
+        }
+    }
+
+    #[test]
+    fn never_bust_bot_stops_where_a_card_could_bust() {
+        let bot = Controller::NeverBust;
+        assert_eq!(choose(bot, &hand(&[Six, Five]), Ten, FIRST_TWO, 0.0), Hit);
+        assert_eq!(choose(bot, &hand(&[Ten, Two]), Ten, FIRST_TWO, 0.0), Stand);
+        assert_eq!(choose(bot, &hand(&[Ace, Six]), Ten, FIRST_TWO, 0.0), Hit);
+        assert_eq!(choose(bot, &hand(&[Ace, Seven]), Ten, FIRST_TWO, 0.0), Stand);
+    }
+
+    #[test]
+    fn dealer_mimic_bot_hits_below_17() {
+        let bot = Controller::DealerMimic;
+        assert_eq!(choose(bot, &hand(&[Ten, Six]), Two, FIRST_TWO, 0.0), Hit);
+        assert_eq!(choose(bot, &hand(&[Ten, Seven]), Ace, FIRST_TWO, 0.0), Stand);
+        assert_eq!(choose(bot, &hand(&[Ace, Six]), Ten, FIRST_TWO, 0.0), Stand);
+    }
+
+    #[test]
+    fn random_bot_stays_legal() {
+        let cards = hand(&[Eight, Eight]);
+        for _ in 0..100 {
+            assert!(NO_SURRENDER.contains(&choose(Controller::Random, &cards, Ten, NO_SURRENDER, 0.0)));
+        }
+        assert_eq!(choose(Controller::Random, &cards, Ten, &[Stand], 0.0), Stand);
+    }
+}
@@ -1,24 +1,116 @@
+use crate::card_art;
+use crate::config::CardStyle;
+use crate::t;
+use rand::Rng;
 use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Serialized as the rank symbol ("2"–"10", "J", "Q", "K", "A").
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Rank {
+    #[serde(rename = "2")]
     Two,
+    #[serde(rename = "3")]
     Three,
+    #[serde(rename = "4")]
     Four,
+    #[serde(rename = "5")]
     Five,
+    #[serde(rename = "6")]
     Six,
+    #[serde(rename = "7")]
     Seven,
+    #[serde(rename = "8")]
     Eight,
+    #[serde(rename = "9")]
     Nine,
+    #[serde(rename = "10")]
     Ten,
+    #[serde(rename = "J")]
     Jack,
+    #[serde(rename = "Q")]
     Queen,
+    #[serde(rename = "K")]
     King,
+    #[serde(rename = "A")]
     Ace,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Suit::Spades => "♠",
+            Suit::Hearts => "♥",
+            Suit::Diamonds => "♦",
+            Suit::Clubs => "♣",
+        }
+    }
+
+    /// ASCII stand-in for terminals without the suit glyphs.
+    pub fn letter(&self) -> &'static str {
+        match self {
+            Suit::Spades => "S",
+            Suit::Hearts => "H",
+            Suit::Diamonds => "D",
+            Suit::Clubs => "C",
+        }
+    }
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            Suit::Spades => "spades",
+            Suit::Hearts => "hearts",
+            Suit::Diamonds => "diamonds",
+            Suit::Clubs => "clubs",
+        }
+    }
+}
+
+/// Serialized as the rank and suit letter ("10H", "QS"). A rank alone still
+/// parses, as spades, since the suit never changes a hand's value.
 #[derive(Clone, Copy, Debug)]
-pub struct Card(pub Rank);
+pub struct Card(pub Rank, pub Suit);
+
+impl Serialize for Card {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{}{}", self.symbol(), self.1.letter()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Card::parse(&text).ok_or_else(|| serde::de::Error::custom(format!("unknown card {:?}", text)))
+    }
+}
+
+impl Card {
+    fn parse(text: &str) -> Option<Card> {
+        use serde::de::IntoDeserializer;
+        let (rank, suit) = match Suit::ALL.iter().find(|suit| text.ends_with(suit.letter())) {
+            Some(&suit) => (&text[..text.len() - 1], suit),
+            None => (text, Suit::Spades),
+        };
+        let rank: Result<Rank, serde::de::value::Error> = Rank::deserialize(rank.into_deserializer());
+        Some(Card(rank.ok()?, suit))
+    }
+}
 
 impl Card {
     pub fn value(&self) -> u8 {
@@ -40,6 +132,25 @@ impl Card {
         matches!(self.0, Rank::Ace)
     }
     
+    /// Rank alone ("2".."10", "J", "Q", "K", "A").
+    pub fn symbol(&self) -> &'static str {
+        match self.0 {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        }
+    }
+
     pub fn short(&self) -> &'static str {
         match self.0 {
             Rank::Two => "2",
@@ -59,21 +170,60 @@ impl Card {
     }
 }
 
+impl Card {
+    /// Full name for screen readers ("ace of spades, counted as eleven").
+    /// Aces and face cards say what they count as; `ace_as_one` picks the
+    /// low value for an ace.
+    pub fn spoken(&self, ace_as_one: bool) -> String {
+        let rank = match self.0 {
+            Rank::Two => "two",
+            Rank::Three => "three",
+            Rank::Four => "four",
+            Rank::Five => "five",
+            Rank::Six => "six",
+            Rank::Seven => "seven",
+            Rank::Eight => "eight",
+            Rank::Nine => "nine",
+            Rank::Ten => "ten",
+            Rank::Jack => "jack",
+            Rank::Queen => "queen",
+            Rank::King => "king",
+            Rank::Ace => "ace",
+        };
+        let name = t!(
+            "card.name",
+            rank = t!(&format!("card.rank.{}", rank)),
+            suit = t!(&format!("card.suit.{}", self.1.key()))
+        );
+        let value = match self.0 {
+            Rank::Ace if ace_as_one => 1,
+            Rank::Ace | Rank::Jack | Rank::Queen | Rank::King => self.value(),
+            _ => return name,
+        };
+        t!("card.counted_as", card = name, value = t!(&format!("card.value.{}", value)))
+    }
+
+    /// The card in running text: the spoken name when cards are read out,
+    /// otherwise the short form.
+    pub fn announce(&self) -> String {
+        if style() == CardStyle::Spoken { self.spoken(false) } else { self.short().to_string() }
+    }
+}
+
 pub fn build_deck() -> Vec<Card> {
     use Rank::*;
     let ranks = [Two, Three, Four, Five, Six, Seven, Eight, Nine, Ten, Jack, Queen, King, Ace];
     let mut deck = Vec::with_capacity(52);
-    for _ in 0..4 {
+    for suit in Suit::ALL {
         for &r in &ranks {
-            deck.push(Card(r));
+            deck.push(Card(r, suit));
         }
     }
     deck
 }
 
-pub fn shuffle_deck(deck: &mut [Card]) {
-    let mut rng = rand::rng();
-    deck.shuffle(&mut rng);
+pub fn shuffle_deck<R: Rng + ?Sized>(deck: &mut [Card], rng: &mut R) {
+    deck.shuffle(rng);
 }
 
 pub fn hand_value(hand: &[Card]) -> u8 {
@@ -90,20 +240,78 @@ pub fn is_blackjack(hand: &[Card]) -> bool {
     hand.len() == 2 && hand_value(hand) == 21
 }
 
+static STYLE: Mutex<CardStyle> = Mutex::new(CardStyle::Plain);
+
+/// How `print_hand` draws cards from now on (`card_style` in game_config.toml).
+pub fn set_style(style: CardStyle) {
+    if let Ok(mut current) = STYLE.lock() {
+        *current = style;
+    }
+}
+
+pub fn style() -> CardStyle {
+    STYLE.lock().map_or(CardStyle::Plain, |s| *s)
+}
+
 pub fn print_hand(name: &str, hand: &[Card], hide_first: bool) {
-    print!("{}: ", name);
-    
-    // Calculate how many aces are being counted as 1
+    match style() {
+        CardStyle::Plain => print_plain(name, hand, hide_first),
+        CardStyle::Spoken => print_spoken(name, hand, hide_first),
+        style => {
+            let total = if hide_first { String::new() } else { format!(" ({})", hand_value(hand)) };
+            println!("{}:{}", name, total);
+            let cards: Vec<Option<Card>> =
+                hand.iter().enumerate().map(|(i, &c)| (i > 0 || !hide_first).then_some(c)).collect();
+            for line in card_art::render(&cards, style == CardStyle::Ascii, card_art::color_supported()) {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Number of aces that must count as 1 to keep the hand at 21 or under.
+fn aces_as_one(hand: &[Card]) -> usize {
     let mut sum: u8 = hand.iter().map(|c| c.value()).sum();
     let mut aces = hand.iter().filter(|c| c.is_ace()).count();
-    let mut aces_as_1 = 0;
+    let mut as_one = 0;
     while sum > 21 && aces > 0 {
         sum -= 10;
         aces -= 1;
-        aces_as_1 += 1;
+        as_one += 1;
     }
+    as_one
+}
+
+/// One sentence naming every card and the total.
+fn print_spoken(name: &str, hand: &[Card], hide_first: bool) {
+    let mut low_aces = aces_as_one(hand);
+    let cards: Vec<String> = hand
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            if i == 0 && hide_first {
+                return t!("card.hidden");
+            }
+            let as_one = c.is_ace() && low_aces > 0;
+            if as_one {
+                low_aces -= 1;
+            }
+            c.spoken(as_one)
+        })
+        .collect();
+    let cards = cards.join(&t!("card.separator"));
+    if hide_first {
+        println!("{}", t!("card.hand_hidden", name = name, cards = cards));
+    } else {
+        println!("{}", t!("card.hand", name = name, cards = cards, total = hand_value(hand)));
+    }
+}
+
+/// One line of rank(value) pairs, for logs.
+fn print_plain(name: &str, hand: &[Card], hide_first: bool) {
+    print!("{}: ", name);
     
-    let mut current_aces_as_1 = aces_as_1;
+    let mut current_aces_as_1 = aces_as_one(hand);
     
     for (i, c) in hand.iter().enumerate() {
         if i == 0 && hide_first {
@@ -0,0 +1,59 @@
+//! Box-drawn cards for the terminal game (`card_style = "unicode"` or
+//! `"ascii"`). A hand is rendered as five text rows with the cards side by
+//! side; `None` is a face-down card.
+
+use crate::card::Card;
+use std::env;
+use std::io::{self, IsTerminal};
+
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+struct Frame {
+    top: &'static str,
+    bottom: &'static str,
+    side: &'static str,
+    back: &'static str,
+}
+
+const UNICODE: Frame = Frame { top: "┌─────┐", bottom: "└─────┘", side: "│", back: "░░░░░" };
+const ASCII: Frame = Frame { top: "+-----+", bottom: "+-----+", side: "|", back: "#####" };
+
+/// Colors only on a terminal, and never with `NO_COLOR` or `TERM=dumb`.
+pub fn color_supported() -> bool {
+    io::stdout().is_terminal()
+        && env::var_os("NO_COLOR").is_none()
+        && env::var("TERM").map_or(true, |term| term != "dumb")
+}
+
+/// Rows of the hand, ready to print one per line.
+pub fn render(cards: &[Option<Card>], ascii: bool, color: bool) -> Vec<String> {
+    let frame = if ascii { &ASCII } else { &UNICODE };
+    let mut rows = vec![String::new(); 5];
+    for (i, card) in cards.iter().enumerate() {
+        let gap = if i == 0 { "" } else { " " };
+        let faces = match card {
+            Some(card) => face(card, ascii, color),
+            None => [frame.back.to_string(), frame.back.to_string(), frame.back.to_string()],
+        };
+        rows[0].push_str(gap);
+        rows[0].push_str(frame.top);
+        for (row, inner) in rows[1..4].iter_mut().zip(faces) {
+            row.push_str(gap);
+            row.push_str(frame.side);
+            row.push_str(&inner);
+            row.push_str(frame.side);
+        }
+        rows[4].push_str(gap);
+        rows[4].push_str(frame.bottom);
+    }
+    rows
+}
+
+/// The three inner lines: rank top-left, suit in the middle, rank bottom-right.
+fn face(card: &Card, ascii: bool, color: bool) -> [String; 3] {
+    let rank = card.symbol();
+    let suit = if ascii { card.1.letter() } else { card.1.symbol() };
+    let paint = |text: String| if color && card.1.is_red() { format!("{}{}{}", RED, text, RESET) } else { text };
+    [paint(format!("{:<5}", rank)), paint(format!("  {}  ", suit)), paint(format!("{:>5}", rank))]
+}
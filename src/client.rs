@@ -0,0 +1,140 @@
+use crate::card::{Card, print_hand};
+use crate::game_action::GameAction;
+use crate::protocol::{ClientMessage, ServerMessage, TableView, send};
+use crate::prompt;
+use crate::t;
+use std::io::{BufRead, BufReader};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+
+/// Terminal client for a `serve` table.
+pub fn run_client(addr: &str, name: &str) -> anyhow::Result<()> {
+    let stream = TcpStream::connect(addr)?;
+    let mut writer = stream.try_clone()?;
+    send(&mut writer, &ClientMessage::Hello { name: name.to_string() })?;
+    println!("{}", t!("client.connected", addr = addr));
+
+    // read on a separate thread so server lines queue up while a prompt is open
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            match serde_json::from_str::<ServerMessage>(&line) {
+                Ok(message) => {
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => eprintln!("{}", t!("client.bad_message", error = e)),
+            }
+        }
+    });
+
+    let mut currency = String::new();
+    let mut shown_dealer: Vec<Option<Card>> = Vec::new();
+    for message in rx {
+        let reply = match message {
+            ServerMessage::Welcome { seat, bank, currency: c, timeout_secs } => {
+                currency = c;
+                println!("{}", t!("client.seated", seat = seat + 1, bank = bank, currency = currency, timeout = timeout_secs));
+                None
+            }
+            ServerMessage::Event { text } => {
+                println!("{}", text);
+                None
+            }
+            ServerMessage::State(view) => {
+                // the table is redrawn on the deal and when the dealer's hand changes
+                let dealer_changed = view.dealer.len() != shown_dealer.len()
+                    || view.dealer.iter().zip(&shown_dealer).any(|(a, b)| a.is_some() != b.is_some());
+                if dealer_changed {
+                    print_table(&view);
+                    shown_dealer = view.dealer;
+                }
+                None
+            }
+            ServerMessage::BetRequest { min, max, suggested } => {
+                let amount = prompt::number(
+                    &t!("bet.input", min = min, max = max, currency = currency),
+                    Some(suggested.clamp(min, max)),
+                    |v| if (min..=max).contains(v) { Ok(()) } else { Err(t!("bet.out_of_range", min = min, max = max)) },
+                );
+                Some(amount.map(|amount| ClientMessage::Bet { amount }))
+            }
+            ServerMessage::InsuranceRequest { cost } => Some(
+                prompt::select(
+                    &t!("play.insurance_prompt", cost = cost, currency = currency),
+                    &[t!("menu.yes"), t!("menu.no")],
+                    1,
+                )
+                .map(|selection| ClientMessage::Insurance { take: selection == 0 }),
+            ),
+            ServerMessage::ActionRequest { hand, cards, legal, .. } => {
+                print_hand(&t!("client.your_hand", hand = hand + 1), &cards, false);
+                Some(
+                    prompt::select(&t!("play.prompt"), &GameAction::menu_items(&legal), 0)
+                        .map(|selection| ClientMessage::Action { action: legal[selection] }),
+                )
+            }
+            ServerMessage::Result(result) => {
+                println!("\n{}", t!("game.table_result", dealer = result.dealer_value));
+                for seat in &result.seats {
+                    let outcomes: Vec<String> = seat.outcomes.iter().map(|o| o.label()).collect();
+                    println!(
+                        "{}",
+                        t!(
+                            "game.table_seat",
+                            name = seat.name,
+                            outcomes = outcomes.join(", "),
+                            net = format!("{:+}", seat.net),
+                            bank = seat.bank,
+                            currency = currency
+                        )
+                    );
+                }
+                shown_dealer.clear();
+                None
+            }
+            ServerMessage::Error { message } => {
+                println!("{}", t!("client.server_message", message = message));
+                None
+            }
+        };
+        match reply {
+            Some(Ok(reply)) => send(&mut writer, &reply)?,
+            // Ctrl+C at a prompt leaves the table
+            Some(Err(_)) => {
+                let _ = send(&mut writer, &ClientMessage::Leave);
+                println!("\n{}", t!("client.left"));
+                return Ok(());
+            }
+            None => {}
+        }
+    }
+    println!("{}", t!("client.disconnected"));
+    Ok(())
+}
+
+fn print_table(view: &TableView) {
+    println!();
+    let hidden = view.dealer.first().is_some_and(Option::is_none);
+    let dealer: Vec<Card> = view.dealer.iter().flatten().copied().collect();
+    if hidden {
+        // print_hand hides the first card, so put a placeholder in its place
+        let mut shown = vec![dealer[0]];
+        shown.extend(&dealer);
+        print_hand(&t!("game.dealer"), &shown, true);
+    } else {
+        print_hand(&t!("game.dealer"), &dealer, false);
+    }
+    for seat in &view.seats {
+        for (i, hand) in seat.hands.iter().enumerate() {
+            let label = if seat.hands.len() > 1 { t!("game.named_hand", name = seat.name, hand = i + 1) } else { seat.name.clone() };
+            print_hand(&label, &hand.cards, false);
+        }
+    }
+    println!("{}", t!("game.shoe_remaining", cards = view.shoe_remaining));
+}
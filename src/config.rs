@@ -1,8 +1,16 @@
-use serde::Deserialize;
-use std::fs;
-use std::path::Path;
+use crate::advisor::BetAdvisorConfig;
+use crate::betting::BettingSystem;
+use crate::config_check::{self, Checked};
+use crate::config_layers::{self, Input, Layer, PROJECT_FILE};
+use crate::deviations::Deviations;
+use crate::rules::{Preset, RuleOverrides, Rules};
+use crate::t;
+use crate::table::TableConfig;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct GameConfig {
     pub currency_name: String,
     pub currency_full_name: String,
@@ -10,6 +18,58 @@ pub struct GameConfig {
     pub bet_amount: u32,
     /// house edge bias: positive gives advantage to player (for "winning" Blackjack)
     pub player_edge: f32,
+    pub table_min_bet: u32,
+    pub table_max_bet: u32,
+    /// progression that suggests the next bet from previous results
+    pub betting_system: BettingSystem,
+    /// named rule set; fields given in `[rules]` override it
+    pub preset: Option<Preset>,
+    /// `[rules]` as written in the file
+    #[serde(rename = "rules")]
+    pub rule_overrides: RuleOverrides,
+    /// rules in effect: the preset (or the defaults) with the overrides applied
+    #[serde(skip)]
+    pub rules: Rules,
+    pub bet_advisor: BetAdvisorConfig,
+    pub table: TableConfig,
+    /// show the recommended play (with count deviations) during a hand
+    pub show_hints: bool,
+    /// user index plays added to the built-in Illustrious 18 / Fab 4
+    pub deviations_file: String,
+    #[serde(skip)]
+    pub deviations: Deviations,
+    /// message language: "ja", "en" or a catalog in lang/<code>.toml
+    pub lang: String,
+    /// how actions are chosen during a hand
+    pub input_mode: InputMode,
+    /// how hands are drawn in the terminal game
+    pub card_style: CardStyle,
+    /// screen-reader mode: spoken cards, full sentences, numbered prompts, no color
+    pub accessible: bool,
+    /// file the settings screen saves to: `--config`, else game_config.toml
+    #[serde(skip)]
+    pub file: PathBuf,
+}
+
+/// `plain`: one line of ranks; `unicode` / `ascii`: small box cards side by
+/// side; `spoken`: each card named in full, for screen readers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardStyle {
+    #[default]
+    Plain,
+    Unicode,
+    Ascii,
+    Spoken,
+}
+
+/// `menu`: arrow keys and Enter; `keys`: one keypress per action (h/s/d/p/r).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputMode {
+    #[default]
+    Menu,
+    Keys,
 }
 
 impl Default for GameConfig {
@@ -20,17 +80,86 @@ impl Default for GameConfig {
             player_starting_bank: 1000,
             bet_amount: 50,
             player_edge: 0.0,
+            table_min_bet: 1,
+            table_max_bet: 500,
+            betting_system: BettingSystem::Flat,
+            preset: None,
+            rule_overrides: RuleOverrides::default(),
+            rules: Rules::default(),
+            bet_advisor: BetAdvisorConfig::default(),
+            table: TableConfig::default(),
+            show_hints: false,
+            deviations_file: "deviations.toml".to_string(),
+            deviations: Deviations::builtin(),
+            lang: "ja".to_string(),
+            input_mode: InputMode::Menu,
+            card_style: CardStyle::Plain,
+            accessible: false,
+            file: PathBuf::from(PROJECT_FILE),
         }
     }
 }
 
 impl GameConfig {
+    /// Reads and checks `path` alone. Unknown keys are warned about on
+    /// stderr; any other problem fails the load with its line and column.
     pub fn load(path: &Path) -> anyhow::Result<Self> {
-        if !path.exists() {
-            return Ok(Self::default());
+        let mut cfg = Self::from_checked(config_check::check_file(path))?;
+        cfg.file = path.to_path_buf();
+        Ok(cfg)
+    }
+
+    /// Reads and checks every layer and merges them, as `load` does for one file.
+    pub fn load_layers(inputs: &[Input]) -> anyhow::Result<Self> {
+        let mut cfg = Self::from_checked(config_check::check_layers(inputs))?;
+        for input in inputs {
+            if let Input::File { layer: Layer::Project, path, .. } = input {
+                cfg.file = path.clone();
+            }
+        }
+        Ok(cfg)
+    }
+
+    fn from_checked(checked: Checked) -> anyhow::Result<Self> {
+        for warning in checked.warnings() {
+            eprintln!("{}", warning.render());
+        }
+        if checked.has_errors() {
+            let errors: Vec<String> = checked.errors().map(|e| e.render()).collect();
+            anyhow::bail!("{}\n{}", errors.join("\n"), t!("config.load_failed", count = errors.len()));
         }
-        let s = fs::read_to_string(path)?;
-        let cfg: Self = toml::from_str(&s)?;
+        // without errors every file parsed
+        Ok(checked.config.unwrap_or_default())
+    }
+
+    /// A copy with the dotted `key` (as written in the file, e.g.
+    /// `rules.decks`) set to `value`; an error when the key does not take
+    /// that value. Ranges are left to `config_check::check_values`.
+    pub fn with_value(&self, key: &str, value: toml::Value) -> anyhow::Result<Self> {
+        let mut table = toml::Table::try_from(self)?;
+        config_layers::insert(&mut table, key, value);
+        let mut cfg: Self = toml::Value::Table(table).try_into()?;
+        cfg.deviations = self.deviations.clone();
+        cfg.file = self.file.clone();
+        cfg.apply_rules();
         Ok(cfg)
     }
+
+    /// Recomputes `rules` from the preset and the `[rules]` overrides.
+    pub fn apply_rules(&mut self) {
+        self.rules = self.preset_rules(self.preset);
+    }
+
+    /// The rules `preset` would give with the `[rules]` overrides applied.
+    pub fn preset_rules(&self, preset: Option<Preset>) -> Rules {
+        let mut rules = preset.map_or_else(Rules::default, |p| p.rules());
+        self.rule_overrides.apply(&mut rules);
+        rules
+    }
+
+    /// Switches to `preset`, keeping the fields set in `[rules]`.
+    pub fn set_preset(&mut self, preset: Preset) {
+        self.preset = Some(preset);
+        self.apply_rules();
+    }
 }
@@ -0,0 +1,773 @@
+//! Validation of the config layers (`config check`, and every load).
+//! Syntax and type errors, values out of range and unknown keys are each
+//! reported with the file and line and column (or the variable or flag) that
+//! set them, the allowed values and a suggested fix.
+
+use crate::config::GameConfig;
+use crate::config_layers::{Entry, Input, Layer, Source, insert};
+use crate::deviations::Deviations;
+use crate::i18n::Catalog;
+use crate::rules::{Preset, Rules};
+use crate::t;
+use crate::table::{MAX_SEATS, SeatConfig};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use toml::de::{DeTable, DeValue};
+
+/// Stop re-reading the file after this many type errors.
+const MAX_TYPE_ERRORS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    /// the file still loads, e.g. an unknown key that is ignored
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Problem {
+    pub severity: Severity,
+    /// dotted path such as `rules.decks` or `table.seats[1].player`
+    pub key: String,
+    /// file, environment variable or flag the value came from; empty when
+    /// no layer sets it and no file was read
+    pub origin: String,
+    /// 1-based line and column; `None` outside a file
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+    pub allowed: Option<String>,
+    pub fix: Option<String>,
+}
+
+impl Problem {
+    /// `file:line:col: error: message`, then the allowed values and the fix
+    /// on indented lines.
+    pub fn render(&self) -> String {
+        let severity = match self.severity {
+            Severity::Error => t!("config.error"),
+            Severity::Warning => t!("config.warning"),
+        };
+        let mut out = match (self.origin.as_str(), self.position) {
+            ("", _) => format!("{}: {}", severity, self.message),
+            (origin, Some((line, col))) => format!("{}:{}:{}: {}: {}", origin, line, col, severity, self.message),
+            (origin, None) => format!("{}: {}: {}", origin, severity, self.message),
+        };
+        if let Some(allowed) = &self.allowed {
+            out.push_str(&format!("\n  {}", t!("config.allowed", allowed = allowed)));
+        }
+        if let Some(fix) = &self.fix {
+            out.push_str(&format!("\n  {}", t!("config.fix", fix = fix)));
+        }
+        out
+    }
+}
+
+/// Result of checking the layers: the merged config (with defaults in place
+/// of bad values) unless a file could not be read or parsed, every problem
+/// found, and where each setting came from.
+#[derive(Debug, Default)]
+pub struct Checked {
+    pub config: Option<GameConfig>,
+    pub problems: Vec<Problem>,
+    /// key path -> the layer that set it last
+    pub sources: BTreeMap<String, Source>,
+    /// the files that were read, lowest priority first
+    pub files: Vec<String>,
+}
+
+impl Checked {
+    pub fn errors(&self) -> impl Iterator<Item = &Problem> {
+        self.problems.iter().filter(|p| p.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Problem> {
+        self.problems.iter().filter(|p| p.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// Where `key` was set, `None` for a default.
+    pub fn source(&self, key: &str) -> Option<&Source> {
+        lookup(&self.sources, key)
+    }
+}
+
+/// Checks the file at `path` alone; a missing file is an error here.
+pub fn check_file(path: &Path) -> Checked {
+    check_layers(&[Input::File { layer: Layer::Project, path: path.to_path_buf(), required: true }])
+}
+
+/// Value checks alone, for a config changed in memory (the settings screen).
+pub fn check_values(cfg: &GameConfig) -> Vec<Problem> {
+    let mut checker = Checker::default();
+    checker.values(&mut cfg.clone());
+    checker.problems
+}
+
+/// Checks each layer, lays them over the defaults in order and checks the
+/// values of the result.
+pub fn check_layers(inputs: &[Input]) -> Checked {
+    let schema = schema();
+    let mut checker = Checker::default();
+    let mut merged = toml::Table::new();
+    for input in inputs {
+        let layer = match input {
+            Input::File { layer, path, required } => checker.file(*layer, path, *required, &schema),
+            Input::Values { layer, entries } => Some(checker.entries(*layer, entries, &schema)),
+        };
+        if let Some((table, sources)) = layer {
+            merge(&mut merged, table, "", &mut checker.sources);
+            checker.sources.extend(sources);
+        }
+    }
+    let config = if checker.failed {
+        None
+    } else {
+        match toml::Value::Table(merged).try_into::<GameConfig>() {
+            Ok(mut cfg) => {
+                cfg.apply_rules();
+                checker.values(&mut cfg);
+                Some(cfg)
+            }
+            Err(error) => {
+                let (origin, position) = checker.position("");
+                checker.problems.push(Problem {
+                    severity: Severity::Error,
+                    key: String::new(),
+                    origin,
+                    position,
+                    message: t!("config.bad_value", key = "", error = error.message()),
+                    allowed: None,
+                    fix: Some(t!("config.bad_value_fix")),
+                });
+                None
+            }
+        }
+    };
+    // layer order, then file order; problems of keys no layer sets last
+    let origins = checker.origins;
+    checker.problems.sort_by_key(|p| {
+        let rank = origins.iter().position(|o| *o == p.origin).unwrap_or(usize::MAX);
+        (rank, p.position.unwrap_or((usize::MAX, 0)))
+    });
+    Checked { config, problems: checker.problems, sources: checker.sources, files: checker.files }
+}
+
+/// Every key the config understands, as the defaults serialize. Optional
+/// fields are left out when unset, so the sample fills them in.
+fn schema() -> toml::Table {
+    let mut sample = GameConfig { preset: Some(Preset::LasVegasStrip), ..GameConfig::default() };
+    sample.table.seats = vec![SeatConfig { bank: Some(0), ..SeatConfig::default() }];
+    let mut schema = toml::Table::try_from(sample).unwrap_or_default();
+    if let Ok(rules) = toml::Value::try_from(Rules::default()) {
+        schema.insert("rules".to_string(), rules);
+    }
+    schema
+}
+
+/// One file being checked.
+struct SourceFile<'a> {
+    layer: Layer,
+    origin: String,
+    text: &'a str,
+    /// key path -> (span of the key, span of the value)
+    spans: BTreeMap<String, (Range<usize>, Range<usize>)>,
+    /// every value that is not a table, with where it starts
+    sources: BTreeMap<String, Source>,
+}
+
+impl SourceFile<'_> {
+    fn index(&mut self, table: &DeTable, prefix: &str) {
+        for (key, value) in table.iter() {
+            let path = join(prefix, key.get_ref());
+            self.spans.insert(path.clone(), (key.span(), value.span()));
+            self.index_value(value.get_ref(), value.span(), &path);
+        }
+    }
+
+    fn index_value(&mut self, value: &DeValue, span: Range<usize>, path: &str) {
+        match value {
+            DeValue::Table(table) => self.index(table, path),
+            DeValue::Array(items) => {
+                self.record(path, span.start);
+                for (i, item) in items.iter().enumerate() {
+                    let path = format!("{}[{}]", path, i);
+                    self.spans.insert(path.clone(), (item.span(), item.span()));
+                    self.index_value(item.get_ref(), item.span(), &path);
+                }
+            }
+            _ => self.record(path, span.start),
+        }
+    }
+
+    fn record(&mut self, path: &str, offset: usize) {
+        let source = Source {
+            layer: self.layer,
+            origin: self.origin.clone(),
+            position: Some(line_col(self.text, offset)),
+        };
+        self.sources.insert(path.to_string(), source);
+    }
+
+    fn problem(&self, severity: Severity, key: String, offset: usize, message: String) -> Problem {
+        Problem {
+            severity,
+            key,
+            origin: self.origin.clone(),
+            position: Some(line_col(self.text, offset)),
+            message,
+            allowed: None,
+            fix: None,
+        }
+    }
+
+    fn unknown_keys(&self, table: &DeTable, known: &toml::Table, prefix: &str, problems: &mut Vec<Problem>) {
+        for (key, value) in table.iter() {
+            let path = join(prefix, key.get_ref());
+            match (known.get(key.get_ref().as_ref()), value.get_ref()) {
+                (None, _) => {
+                    let message = t!("config.unknown_key", key = path);
+                    problems.push(Problem {
+                        allowed: Some(known_keys(known)),
+                        fix: Some(unknown_key_fix(prefix, key.get_ref(), known)),
+                        ..self.problem(Severity::Warning, path, key.span().start, message)
+                    });
+                }
+                (Some(toml::Value::Table(inner)), DeValue::Table(table)) => {
+                    self.unknown_keys(table, inner, &path, problems)
+                }
+                (Some(toml::Value::Array(samples)), DeValue::Array(items)) => {
+                    let Some(toml::Value::Table(inner)) = samples.first() else { continue };
+                    for (i, item) in items.iter().enumerate() {
+                        if let DeValue::Table(table) = item.get_ref() {
+                            self.unknown_keys(table, inner, &format!("{}[{}]", path, i), problems);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the file as a table, reporting each value of the wrong type.
+    /// The bad entry is blanked out (keeping byte offsets) and the rest read
+    /// again, so one typo does not hide the next.
+    fn deserialize(&mut self, problems: &mut Vec<Problem>) -> Option<toml::Table> {
+        let mut text = self.text.to_string();
+        for _ in 0..MAX_TYPE_ERRORS {
+            let error = match toml::from_str::<GameConfig>(&text) {
+                Ok(_) => return toml::from_str(&text).ok(),
+                Err(error) => error,
+            };
+            let span = error.span().unwrap_or(0..0);
+            let entry = self.innermost(&span);
+            let key = entry.as_ref().map_or(String::new(), |(path, _)| path.clone());
+            let (message, allowed) = match error.message().split_once(", expected ") {
+                Some((message, expected)) => (message.to_string(), Some(expected_label(expected))),
+                None => (error.message().to_string(), None),
+            };
+            let message = t!("config.bad_value", key = key, error = message);
+            problems.push(Problem {
+                allowed,
+                fix: Some(t!("config.bad_value_fix")),
+                ..self.problem(Severity::Error, key, span.start, message)
+            });
+            let (path, range) = entry?;
+            self.sources.retain(|key, _| !within(key, &path));
+            text.replace_range(range.clone(), &blank(&text[range]));
+        }
+        None
+    }
+
+    /// Key path and full `key = value` range of the smallest entry around `span`.
+    fn innermost(&self, span: &Range<usize>) -> Option<(String, Range<usize>)> {
+        self.spans
+            .iter()
+            .map(|(path, (key, value))| (path, key.start.min(value.start)..key.end.max(value.end)))
+            .filter(|(_, range)| range.start <= span.start && span.end <= range.end && !range.is_empty())
+            .min_by_key(|(_, range)| range.len())
+            .map(|(path, range)| (path.clone(), range))
+    }
+}
+
+type Layered = (toml::Table, BTreeMap<String, Source>);
+
+#[derive(Default)]
+struct Checker {
+    problems: Vec<Problem>,
+    /// key path -> where the value in the merged table came from
+    sources: BTreeMap<String, Source>,
+    /// files, variables and flags in the order they were read, for sorting
+    origins: Vec<String>,
+    files: Vec<String>,
+    /// the last file read, blamed for keys no layer sets
+    default_origin: String,
+    /// a file could not be read or parsed
+    failed: bool,
+}
+
+impl Checker {
+    /// Checks one file. `None` when it is missing (fine unless `required`)
+    /// or cannot be used at all.
+    fn file(&mut self, layer: Layer, path: &Path, required: bool, schema: &toml::Table) -> Option<Layered> {
+        let origin = path.display().to_string();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) if !required && !path.exists() => return None,
+            Err(error) => {
+                let message = if path.exists() {
+                    t!("config.unreadable", path = &origin, error = error)
+                } else {
+                    t!("config.missing_file", path = &origin)
+                };
+                self.problems.push(Problem {
+                    severity: Severity::Error,
+                    key: String::new(),
+                    origin,
+                    position: None,
+                    message,
+                    allowed: None,
+                    fix: Some(t!("config.missing_file_fix")),
+                });
+                self.failed = true;
+                return None;
+            }
+        };
+        self.origins.push(origin.clone());
+        self.files.push(origin.clone());
+        self.default_origin = origin.clone();
+        let mut file = SourceFile { layer, origin, text: &text, spans: BTreeMap::new(), sources: BTreeMap::new() };
+        let (document, syntax_errors) = DeTable::parse_recoverable(&text);
+        if !syntax_errors.is_empty() {
+            for error in syntax_errors {
+                let offset = error.span().map_or(0, |span| span.start);
+                let message = t!("config.syntax", error = error.message());
+                self.problems.push(Problem {
+                    position: error.span().map(|_| line_col(&text, offset)),
+                    fix: Some(t!("config.syntax_fix")),
+                    ..file.problem(Severity::Error, String::new(), offset, message)
+                });
+            }
+            self.failed = true;
+            return None;
+        }
+        let document = document.into_inner();
+        file.index(&document, "");
+        file.unknown_keys(&document, schema, "", &mut self.problems);
+        let table = file.deserialize(&mut self.problems);
+        if table.is_none() {
+            self.failed = true;
+        }
+        table.map(|table| (table, file.sources))
+    }
+
+    /// Checks settings given as variables or flags. An unknown key is
+    /// skipped with a warning, a value of the wrong type with an error.
+    fn entries(&mut self, layer: Layer, entries: &[Entry], schema: &toml::Table) -> Layered {
+        let mut table = toml::Table::new();
+        let mut sources = BTreeMap::new();
+        for entry in entries {
+            self.origins.push(entry.origin.clone());
+            let problem = Problem {
+                severity: Severity::Error,
+                key: entry.key.clone(),
+                origin: entry.origin.clone(),
+                position: None,
+                message: String::new(),
+                allowed: None,
+                fix: None,
+            };
+            if let Some((prefix, known, part)) = unknown_part(schema, &entry.key) {
+                self.problems.push(Problem {
+                    severity: Severity::Warning,
+                    message: t!("config.unknown_key", key = &entry.key),
+                    allowed: Some(known_keys(known)),
+                    fix: Some(unknown_key_fix(&prefix, part, known)),
+                    ..problem
+                });
+                continue;
+            }
+            let value = match typed(&entry.key, entry.value.clone()) {
+                Ok(value) => value,
+                Err(error) => {
+                    let (message, allowed) = match error.message().split_once(", expected ") {
+                        Some((message, expected)) => (message.to_string(), Some(expected_label(expected))),
+                        None => (error.message().to_string(), None),
+                    };
+                    self.problems.push(Problem {
+                        message: t!("config.bad_value", key = &entry.key, error = message),
+                        allowed,
+                        fix: Some(t!("config.bad_value_fix")),
+                        ..problem
+                    });
+                    continue;
+                }
+            };
+            insert(&mut table, &entry.key, value);
+            sources.insert(entry.key.clone(), Source { layer, origin: entry.origin.clone(), position: None });
+        }
+        (table, sources)
+    }
+
+    /// Where the value of `key` was set, if any layer sets it.
+    fn position(&self, key: &str) -> (String, Option<(usize, usize)>) {
+        match lookup(&self.sources, key) {
+            Some(source) => (source.origin.clone(), source.position),
+            None => (self.default_origin.clone(), None),
+        }
+    }
+
+    fn error(&mut self, key: &str, message: String, allowed: String, fix: String) {
+        let (origin, position) = self.position(key);
+        self.problems.push(Problem {
+            severity: Severity::Error,
+            key: key.to_string(),
+            origin,
+            position,
+            message,
+            allowed: Some(allowed),
+            fix: Some(fix),
+        });
+    }
+
+    /// The fix of setting `key` to `value`, written the way the layer that
+    /// set it takes it.
+    fn set(&self, key: &str, value: impl Display) -> String {
+        match lookup(&self.sources, key) {
+            Some(Source { layer: Layer::Env, origin, .. }) => {
+                t!("config.set_arg", arg = format!("{}={}", origin, value))
+            }
+            Some(Source { layer: Layer::Cli, origin, .. }) => {
+                t!("config.set_arg", arg = format!("{} {}", origin, value))
+            }
+            _ => t!("config.set", key = leaf(key), value = value),
+        }
+    }
+
+    /// Reports an out-of-range number, suggesting the nearest allowed value.
+    fn range<T: PartialOrd + Copy + Display>(&mut self, key: &str, value: T, min: T, max: Option<T>) {
+        let fixed = if value < min {
+            min
+        } else {
+            match max {
+                Some(max) if value > max => max,
+                _ => return,
+            }
+        };
+        let allowed = match max {
+            Some(max) => t!("config.range", min = min, max = max),
+            None => t!("config.at_least", min = min),
+        };
+        let fix = self.set(key, fixed);
+        self.error(key, t!("config.out_of_range", key = key, value = value), allowed, fix);
+    }
+
+    /// Value checks on a config that deserialized.
+    fn values(&mut self, cfg: &mut GameConfig) {
+        self.range("player_starting_bank", cfg.player_starting_bank, 1, None);
+        self.range("player_edge", cfg.player_edge, 0.0, Some(1.0));
+        self.range("table_min_bet", cfg.table_min_bet, 1, None);
+        if cfg.table_min_bet > cfg.table_max_bet {
+            let key = if lookup(&self.sources, "table_max_bet").is_some() { "table_max_bet" } else { "table_min_bet" };
+            self.error(
+                key,
+                t!("config.min_over_max", min = cfg.table_min_bet, max = cfg.table_max_bet),
+                t!("config.at_least", min = cfg.table_min_bet),
+                self.set("table_max_bet", cfg.table_min_bet),
+            );
+        }
+        let max_bet = cfg.table_max_bet.min(cfg.player_starting_bank).max(cfg.table_min_bet);
+        if cfg.bet_amount > cfg.player_starting_bank {
+            self.error(
+                "bet_amount",
+                t!("config.bet_over_bank", bet = cfg.bet_amount, bank = cfg.player_starting_bank),
+                t!("config.range", min = cfg.table_min_bet, max = max_bet),
+                self.set("bet_amount", max_bet),
+            );
+        } else if cfg.bet_amount < cfg.table_min_bet || cfg.bet_amount > cfg.table_max_bet {
+            let fixed = cfg.bet_amount.clamp(cfg.table_min_bet, max_bet);
+            self.error(
+                "bet_amount",
+                t!("config.bet_outside_limits", bet = cfg.bet_amount, min = cfg.table_min_bet, max = cfg.table_max_bet),
+                t!("config.range", min = cfg.table_min_bet, max = max_bet),
+                self.set("bet_amount", fixed),
+            );
+        }
+        self.range("rules.decks", cfg.rules.decks, 1, Some(8));
+        self.range("rules.penetration", cfg.rules.penetration, 0.0, Some(1.0));
+        self.range("rules.max_split_hands", cfg.rules.max_split_hands, 2, Some(8));
+        self.range("bet_advisor.units_per_count", cfg.bet_advisor.units_per_count, 0.0, None);
+        self.range("bet_advisor.max_units", cfg.bet_advisor.max_units, 1, None);
+        self.range("bet_advisor.kelly_fraction", cfg.bet_advisor.kelly_fraction, 0.0, Some(1.0));
+
+        let seats = cfg.table.seats.len();
+        if seats == 0 || seats > MAX_SEATS {
+            self.error(
+                "table.seats",
+                t!("config.seat_count", seats = seats),
+                t!("config.range", min = 1, max = MAX_SEATS),
+                if seats == 0 { t!("config.add_seat") } else { t!("config.remove_seats", extra = seats - MAX_SEATS) },
+            );
+        }
+        for (i, seat) in cfg.table.seats.iter().enumerate() {
+            if seat.name.trim().is_empty() {
+                self.error(
+                    &format!("table.seats[{}].name", i),
+                    t!("config.empty_name", seat = i + 1),
+                    t!("config.non_empty"),
+                    t!("config.set", key = "name", value = format!("\"{}\"", SeatConfig::default().name)),
+                );
+            }
+            if let Some(bank) = seat.bank {
+                self.range(&format!("table.seats[{}].bank", i), bank, 1, None);
+            }
+        }
+
+        if let Err(error) = Catalog::load(&cfg.lang) {
+            self.error(
+                "lang",
+                t!("config.bad_lang", lang = &cfg.lang, error = error),
+                t!("config.lang_values"),
+                self.set("lang", "\"ja\""),
+            );
+        }
+        match Deviations::load(Path::new(&cfg.deviations_file)) {
+            Ok(deviations) => cfg.deviations = deviations,
+            Err(error) => self.error(
+                "deviations_file",
+                t!("config.bad_deviations", file = &cfg.deviations_file, error = error),
+                t!("config.deviations_format"),
+                t!("config.deviations_fix", file = &cfg.deviations_file),
+            ),
+        }
+    }
+}
+
+/// Lays `overlay` over `base`: tables merge key by key, anything else
+/// (arrays included) replaces the lower layer's value whole.
+fn merge(base: &mut toml::Table, overlay: toml::Table, prefix: &str, sources: &mut BTreeMap<String, Source>) {
+    for (key, value) in overlay {
+        let path = join(prefix, &key);
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(inner)), toml::Value::Table(value)) => merge(inner, value, &path, sources),
+            (_, value) => {
+                sources.retain(|key, _| !within(key, &path));
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// `value` if the config accepts it at `key`, else as a string (a player
+/// name of `007` given in a variable), else the first error.
+fn typed(key: &str, value: toml::Value) -> Result<toml::Value, toml::de::Error> {
+    let accepts = |value: &toml::Value| {
+        let mut table = toml::Table::new();
+        insert(&mut table, key, value.clone());
+        toml::Value::Table(table).try_into::<GameConfig>().map(|_| ())
+    };
+    match accepts(&value) {
+        Ok(()) => Ok(value),
+        Err(error) => match value {
+            toml::Value::String(_) => Err(error),
+            value => {
+                let text = toml::Value::String(value.to_string());
+                accepts(&text).map(|_| text).map_err(|_| error)
+            }
+        },
+    }
+}
+
+/// Source of `key`, or of the array it is in when the array was set whole.
+fn lookup<'a>(sources: &'a BTreeMap<String, Source>, key: &str) -> Option<&'a Source> {
+    let mut key = key;
+    loop {
+        if let Some(source) = sources.get(key) {
+            return Some(source);
+        }
+        key = &key[..key.rfind(['.', '['])?];
+    }
+}
+
+/// Whether `key` is `path` or inside it.
+fn within(key: &str, path: &str) -> bool {
+    key.strip_prefix(path).is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+/// The table prefix, known keys and part of a dotted key that the schema
+/// does not have; `None` when the whole key is known.
+fn unknown_part<'a, 'k>(schema: &'a toml::Table, key: &'k str) -> Option<(String, &'a toml::Table, &'k str)> {
+    let mut known = schema;
+    let mut prefix = String::new();
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        match known.get(part) {
+            Some(toml::Value::Table(inner)) if parts.peek().is_some() => {
+                prefix = join(&prefix, part);
+                known = inner;
+            }
+            Some(_) if parts.peek().is_none() => return None,
+            _ => return Some((prefix, known, part)),
+        }
+    }
+    None
+}
+
+fn known_keys(known: &toml::Table) -> String {
+    known.keys().map(String::as_str).collect::<Vec<_>>().join(", ")
+}
+
+/// The known key a typo was probably meant to be, else to remove it.
+fn unknown_key_fix(prefix: &str, key: &str, known: &toml::Table) -> String {
+    if known.contains_key(key) {
+        // a value nested under a key that takes no table
+        return t!("config.remove_key");
+    }
+    match closest(key, known.keys()) {
+        Some(name) => t!("config.did_you_mean", key = join(prefix, name)),
+        None => t!("config.remove_key"),
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) }
+}
+
+/// Last component of a key path, as it is written inside its table.
+fn leaf(key: &str) -> &str {
+    key.rsplit('.').next().unwrap_or(key)
+}
+
+/// 1-based line and column (in characters) of a byte offset.
+fn line_col(source: &str, offset: usize) -> (usize, usize) {
+    let before = source.get(..offset).unwrap_or(source);
+    let line = before.matches('\n').count() + 1;
+    let col = before.rsplit('\n').next().map_or(0, |l| l.chars().count()) + 1;
+    (line, col)
+}
+
+/// Same number of bytes as `text`, all spaces except the line breaks.
+fn blank(text: &str) -> String {
+    text.chars().map(|c| if c == '\n' { "\n".to_string() } else { " ".repeat(c.len_utf8()) }).collect()
+}
+
+/// Readable name for serde's expected type.
+fn expected_label(expected: &str) -> String {
+    match expected {
+        "u8" | "u32" | "u64" => t!("config.integer"),
+        "f32" | "f64" => t!("config.number"),
+        "a boolean" => "true / false".to_string(),
+        "a string" => t!("config.string"),
+        _ => expected.replace("one of ", ""),
+    }
+}
+
+/// Known key within two edits of `key`, for typos.
+fn closest<'a>(key: &str, known: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    known
+        .map(|name| (edit_distance(key, name), name))
+        .filter(|(distance, name)| *distance <= 2.min(name.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let next = (diagonal + usize::from(ca != *cb)).min(row[j] + 1).min(row[j + 1] + 1);
+            diagonal = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn check_text(name: &str, text: &str) -> Checked {
+        let path = std::env::temp_dir().join(format!("veintiuna-check-{}-{}.toml", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        let checked = check_file(&path);
+        let _ = fs::remove_file(&path);
+        checked
+    }
+
+    #[test]
+    fn out_of_range_value_points_at_its_line_and_column() {
+        let checked = check_text("range", "bet_amount = 10\n\n[rules]\ndecks = 20\n");
+        let problem = checked.errors().next().unwrap();
+        assert_eq!(problem.key, "rules.decks");
+        assert_eq!(problem.position, Some((4, 9)));
+    }
+
+    #[test]
+    fn wrong_type_points_at_the_value() {
+        let checked = check_text("type", "currency_name = \"np\"\nbet_amount = \"ten\"\n");
+        let problem = checked.errors().next().unwrap();
+        assert_eq!(problem.key, "bet_amount");
+        assert_eq!(problem.position, Some((2, 14)));
+    }
+
+    #[test]
+    fn syntax_error_has_a_position() {
+        let checked = check_text("syntax", "bet_amount = 10\nrules = [\n");
+        assert!(checked.config.is_none());
+        let problem = checked.errors().next().unwrap();
+        assert_eq!(problem.position, Some((2, 10)));
+    }
+
+    #[test]
+    fn unknown_key_is_a_warning_with_a_suggestion() {
+        let checked = check_text("unknown", "bet_amout = 10\n[rules]\ndecs = 2\n");
+        assert!(!checked.has_errors());
+        let warnings: Vec<&Problem> = checked.warnings().collect();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].key, "bet_amout");
+        assert_eq!(warnings[0].position, Some((1, 1)));
+        assert!(warnings[0].fix.as_deref().unwrap().contains("bet_amount"));
+        assert_eq!(warnings[1].key, "rules.decs");
+        assert!(warnings[1].fix.as_deref().unwrap().contains("rules.decks"));
+    }
+
+    #[test]
+    fn unrelated_key_gets_no_suggestion() {
+        let checked = check_text("unrelated", "completely_different = 1\n");
+        let warning = checked.warnings().next().unwrap();
+        assert_eq!(warning.fix.as_deref(), Some(t!("config.remove_key").as_str()));
+    }
+
+    #[test]
+    fn minimum_above_maximum_is_an_error() {
+        let cfg = GameConfig { table_min_bet: 100, table_max_bet: 10, bet_amount: 100, ..GameConfig::default() };
+        let problems = check_values(&cfg);
+        assert!(problems.iter().any(|p| p.severity == Severity::Error && p.key == "table_min_bet"));
+        assert!(check_values(&GameConfig::default()).iter().all(|p| p.severity != Severity::Error));
+    }
+
+    #[test]
+    fn minimum_above_maximum_blames_the_maximum_in_a_file() {
+        let checked = check_text("limits", "table_min_bet = 100\ntable_max_bet = 10\nbet_amount = 100\n");
+        let problem = checked.errors().next().unwrap();
+        assert_eq!(problem.key, "table_max_bet");
+        assert_eq!(problem.position, Some((2, 17)));
+    }
+
+    #[test]
+    fn edit_distance_counts_single_edits() {
+        assert_eq!(edit_distance("decks", "decks"), 0);
+        assert_eq!(edit_distance("decs", "decks"), 1);
+        assert_eq!(edit_distance("bet_amuont", "bet_amount"), 2);
+    }
+}
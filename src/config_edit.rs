@@ -0,0 +1,168 @@
+//! Writes changed settings back into a TOML file without disturbing the rest
+//! of it: only the text of each changed value is replaced, so comments,
+//! blank lines and key order stay as they were. A key the file does not have
+//! yet is added under its table, which is created at the end if needed.
+
+use crate::t;
+use std::ops::Range;
+use toml::de::{DeTable, DeValue};
+
+/// `source` with each dotted key set to its value.
+pub fn set_values(source: &str, changes: &[(String, toml::Value)]) -> anyhow::Result<String> {
+    let mut text = source.to_string();
+    for (key, value) in changes {
+        text = set_value(&text, key, value)?;
+    }
+    Ok(text)
+}
+
+fn set_value(source: &str, key: &str, value: &toml::Value) -> anyhow::Result<String> {
+    let (document, errors) = DeTable::parse_recoverable(source);
+    if let Some(error) = errors.first() {
+        anyhow::bail!(t!("config.syntax", error = error.message()));
+    }
+    let document = document.into_inner();
+    let (table, leaf) = key.rsplit_once('.').unwrap_or(("", key));
+    let line = format!("{} = {}", leaf, value);
+    let mut text = source.to_string();
+    match find(&document, key) {
+        Some(span) => text.replace_range(span, &value.to_string()),
+        None => match append_at(&document, table) {
+            Some(offset) => {
+                let offset = line_end(&mut text, offset);
+                text.insert_str(offset, &format!("{}\n", line));
+            }
+            None => {
+                if !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&format!("[{}]\n{}\n", table, line));
+            }
+        },
+    }
+    // inline tables and the like are not edited in place; refuse rather than
+    // write a file that no longer says what was asked
+    let (check, errors) = DeTable::parse_recoverable(&text);
+    let written = find(check.get_ref(), key).map(|span| text[span].to_string());
+    if !errors.is_empty() || written != Some(value.to_string()) {
+        anyhow::bail!(t!("settings.write_failed", key = key));
+    }
+    Ok(text)
+}
+
+fn entry<'a, 'i>(table: &'a DeTable<'i>, key: &str) -> Option<&'a toml::Spanned<DeValue<'i>>> {
+    table.iter().find(|(k, _)| k.get_ref().as_ref() == key).map(|(_, v)| v)
+}
+
+/// Span of the value of a dotted key, when the file sets it.
+fn find(table: &DeTable, key: &str) -> Option<Range<usize>> {
+    match key.split_once('.') {
+        Some((head, rest)) => match entry(table, head)?.get_ref() {
+            DeValue::Table(inner) => find(inner, rest),
+            _ => None,
+        },
+        None => entry(table, key).map(|value| value.span()),
+    }
+}
+
+/// Where a new key of `table` goes: after its last plain value, else after
+/// its header. `None` when the file has no such table.
+fn append_at(document: &DeTable, table: &str) -> Option<usize> {
+    let mut current = document;
+    let mut header = None;
+    if !table.is_empty() {
+        for part in table.split('.') {
+            let (key, value) = current.iter().find(|(k, _)| k.get_ref().as_ref() == part)?;
+            let DeValue::Table(inner) = value.get_ref() else { return None };
+            header = Some(key.span().end);
+            current = inner;
+        }
+    }
+    let last = current
+        .iter()
+        .filter(|(_, value)| !matches!(value.get_ref(), DeValue::Table(_)) && !is_table_array(value.get_ref()))
+        .map(|(_, value)| value.span().end)
+        .max();
+    last.or(header).or(Some(0).filter(|_| table.is_empty()))
+}
+
+fn is_table_array(value: &DeValue) -> bool {
+    matches!(value, DeValue::Array(items) if items.iter().any(|item| matches!(item.get_ref(), DeValue::Table(_))))
+}
+
+/// Start of the line after `offset`, adding a final line break if missing.
+/// Offset 0 means the very start of the file.
+fn line_end(text: &mut String, offset: usize) -> usize {
+    if offset == 0 {
+        return 0;
+    }
+    match text[offset..].find('\n') {
+        Some(newline) => offset + newline + 1,
+        None => {
+            text.push('\n');
+            text.len()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GameConfig;
+
+    const SOURCE: &str = "\
+# money
+currency_name = \"np\"   # short name
+bet_amount = 50
+
+[rules]
+# how many decks
+decks = 6
+";
+
+    fn change(key: &str, value: impl Into<toml::Value>) -> (String, toml::Value) {
+        (key.to_string(), value.into())
+    }
+
+    #[test]
+    fn changed_values_keep_comments_and_layout() {
+        let text = set_values(SOURCE, &[change("bet_amount", 25), change("rules.decks", 2)]).unwrap();
+        assert_eq!(text, SOURCE.replace("bet_amount = 50", "bet_amount = 25").replace("decks = 6", "decks = 2"));
+    }
+
+    #[test]
+    fn written_file_reads_back_as_the_new_config() {
+        let changes = [change("currency_name", "nanai"), change("rules.surrender", false), change("show_hints", true)];
+        let text = set_values(SOURCE, &changes).unwrap();
+        let mut cfg: GameConfig = toml::from_str(&text).unwrap();
+        cfg.apply_rules();
+        assert_eq!(cfg.currency_name, "nanai");
+        assert!(!cfg.rules.surrender);
+        assert!(cfg.show_hints);
+        assert_eq!(cfg.bet_amount, 50);
+        for comment in ["# money", "# short name", "# how many decks"] {
+            assert!(text.contains(comment));
+        }
+    }
+
+    #[test]
+    fn missing_keys_go_under_their_table() {
+        let text = set_values(SOURCE, &[change("rules.surrender", true), change("bet_advisor.max_units", 8)]).unwrap();
+        assert!(text.contains("decks = 6\nsurrender = true\n"));
+        assert!(text.ends_with("\n[bet_advisor]\nmax_units = 8\n"));
+    }
+
+    #[test]
+    fn unchanged_round_trip_is_identical() {
+        let text = set_values(SOURCE, &[change("bet_amount", 50)]).unwrap();
+        assert_eq!(text, SOURCE);
+    }
+
+    #[test]
+    fn inline_tables_are_refused() {
+        assert!(set_values("rules = { decks = 6 }\n", &[change("rules.surrender", true)]).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Where settings come from, lowest priority first: the built-in defaults,
+//! the user file in the XDG config directory, `./game_config.toml` (or
+//! `--config`), `VEINTIUNA_*` environment variables, then command-line flags.
+//! `config_check::check_layers` merges and validates them.
+
+use crate::config_check::Checked;
+use crate::t;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+
+/// Project file read from the working directory when `--config` is not given.
+pub const PROJECT_FILE: &str = "game_config.toml";
+/// Prefix of the environment variables; nested keys join with `__`
+/// (`VEINTIUNA_RULES__DECKS`).
+pub const ENV_PREFIX: &str = "VEINTIUNA_";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Default,
+    User,
+    Project,
+    Env,
+    Cli,
+}
+
+impl Layer {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Layer::Default => "default",
+            Layer::User => "user",
+            Layer::Project => "project",
+            Layer::Env => "env",
+            Layer::Cli => "cli",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        t!(&format!("layer.{}", self.key()))
+    }
+}
+
+/// Where one setting was made: the layer, the file, variable or flag, and
+/// the line and column within a file.
+#[derive(Debug, Clone)]
+pub struct Source {
+    pub layer: Layer,
+    pub origin: String,
+    pub position: Option<(usize, usize)>,
+}
+
+/// A setting given outside a file, such as `VEINTIUNA_BET_AMOUNT=25` or `--bet 25`.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// dotted key path, e.g. `rules.decks`
+    pub key: String,
+    /// the variable or flag, for messages
+    pub origin: String,
+    pub value: toml::Value,
+}
+
+pub enum Input {
+    /// a TOML file; a missing file is skipped unless `required`
+    File { layer: Layer, path: PathBuf, required: bool },
+    Values { layer: Layer, entries: Vec<Entry> },
+}
+
+/// Every layer in priority order. `config` is the `--config` path, which
+/// replaces the project file and must exist.
+pub fn inputs(config: Option<PathBuf>, cli: Vec<Entry>) -> Vec<Input> {
+    let mut inputs = Vec::new();
+    if let Some(path) = user_config_path() {
+        inputs.push(Input::File { layer: Layer::User, path, required: false });
+    }
+    let required = config.is_some();
+    let path = config.unwrap_or_else(|| PathBuf::from(PROJECT_FILE));
+    inputs.push(Input::File { layer: Layer::Project, path, required });
+    inputs.push(Input::Values { layer: Layer::Env, entries: env_entries(env::vars()) });
+    inputs.push(Input::Values { layer: Layer::Cli, entries: cli });
+    inputs
+}
+
+/// `$XDG_CONFIG_HOME/veintiuna/config.toml`, else under `~/.config`
+/// (`%APPDATA%` on Windows).
+pub fn user_config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(base.join("veintiuna").join("config.toml"))
+}
+
+/// Settings from `VEINTIUNA_*` variables: `VEINTIUNA_BET_AMOUNT=25` sets
+/// `bet_amount`, `VEINTIUNA_RULES__DECKS=2` sets `rules.decks`.
+pub fn env_entries(vars: impl Iterator<Item = (String, String)>) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = vars
+        .filter_map(|(name, raw)| {
+            let key = name.strip_prefix(ENV_PREFIX)?.to_lowercase().replace("__", ".");
+            Some(Entry { key, value: parse_value(&raw), origin: name })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.origin.cmp(&b.origin));
+    entries
+}
+
+/// A TOML value (`25`, `true`, `0.5`, `"text"`); anything else is taken as
+/// a bare string, so `VEINTIUNA_LANG=en` works without quotes.
+pub fn parse_value(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+/// Sets a dotted key, creating the tables on the way.
+pub(crate) fn insert(table: &mut toml::Table, key: &str, value: toml::Value) {
+    match key.split_once('.') {
+        Some((head, rest)) => {
+            let inner = table.entry(head).or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if let toml::Value::Table(inner) = inner {
+                insert(inner, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
+}
+
+/// `(key, value, source)` for every effective setting, for `config show`.
+/// Rule fields that no layer sets come from the preset, when there is one.
+pub fn show(checked: &Checked) -> Vec<(String, String, String)> {
+    let Some(cfg) = &checked.config else {
+        return Vec::new();
+    };
+    let mut table = toml::Table::try_from(cfg).unwrap_or_default();
+    if let Ok(rules) = toml::Value::try_from(&cfg.rules) {
+        table.insert("rules".to_string(), rules);
+    }
+    let mut values = BTreeMap::new();
+    flatten("", &toml::Value::Table(table), &mut values);
+    values
+        .into_iter()
+        .map(|(key, value)| {
+            let source = match checked.source(&key) {
+                Some(source) => {
+                    let mut origin = source.origin.clone();
+                    if let Some((line, col)) = source.position {
+                        origin = format!("{}:{}:{}", origin, line, col);
+                    }
+                    t!("layer.from", layer = source.layer.label(), origin = origin)
+                }
+                _ => match cfg.preset.filter(|_| key.starts_with("rules.")) {
+                    Some(preset) => t!("layer.preset", name = preset.label()),
+                    None => Layer::Default.label(),
+                },
+            };
+            (key, value, source)
+        })
+        .collect()
+}
+
+fn flatten(prefix: &str, value: &toml::Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&path, value, out);
+            }
+        }
+        toml::Value::Array(items) if items.iter().all(toml::Value::is_table) && !items.is_empty() => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(&format!("{}[{}]", prefix, i), item, out);
+            }
+        }
+        // the config's floats are f32; print them without the f64 widening noise
+        toml::Value::Float(float) => {
+            let short = (*float as f32).to_string();
+            let short = if short.contains(['.', 'e', 'N', 'n']) { short } else { format!("{}.0", short) };
+            out.insert(prefix.to_string(), short);
+        }
+        value => {
+            out.insert(prefix.to_string(), value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_check::check_layers;
+    use std::fs;
+
+    fn vars(pairs: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect::<Vec<_>>().into_iter()
+    }
+
+    fn entry(layer: Layer, key: &str, value: i64) -> Input {
+        let entries = vec![Entry { key: key.to_string(), origin: format!("{:?}", layer), value: value.into() }];
+        Input::Values { layer, entries }
+    }
+
+    fn file(layer: Layer, name: &str, text: &str) -> Input {
+        let path = std::env::temp_dir().join(format!("veintiuna-layers-{}-{}.toml", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        Input::File { layer, path, required: true }
+    }
+
+    #[test]
+    fn double_underscore_nests_env_keys() {
+        let entries = env_entries(vars(&[
+            ("VEINTIUNA_RULES__DECKS", "2"),
+            ("VEINTIUNA_BET_AMOUNT", "25"),
+            ("VEINTIUNA_LANG", "en"),
+            ("HOME", "/root"),
+        ]));
+        let keys: Vec<(&str, &toml::Value)> = entries.iter().map(|e| (e.key.as_str(), &e.value)).collect();
+        assert_eq!(
+            keys,
+            [
+                ("bet_amount", &toml::Value::Integer(25)),
+                ("lang", &toml::Value::String("en".to_string())),
+                ("rules.decks", &toml::Value::Integer(2)),
+            ]
+        );
+        assert_eq!(entries[2].origin, "VEINTIUNA_RULES__DECKS");
+    }
+
+    #[test]
+    fn env_values_read_as_toml_or_bare_strings() {
+        assert_eq!(parse_value("true"), toml::Value::Boolean(true));
+        assert_eq!(parse_value("0.5"), toml::Value::Float(0.5));
+        assert_eq!(parse_value("\"quoted\""), toml::Value::String("quoted".to_string()));
+        assert_eq!(parse_value("nanai points"), toml::Value::String("nanai points".to_string()));
+    }
+
+    #[test]
+    fn later_layers_win() {
+        let user = file(Layer::User, "user", "bet_amount = 20\ncurrency_name = \"u\"\n[rules]\ndecks = 2\n");
+        let project = file(Layer::Project, "project", "bet_amount = 30\ncurrency_name = \"p\"\n");
+        let env = entry(Layer::Env, "bet_amount", 40);
+        let cli = entry(Layer::Cli, "bet_amount", 45);
+
+        let checked = check_layers(&[user, project, env, cli]);
+        for input in &checked.files {
+            let _ = fs::remove_file(input);
+        }
+        let cfg = checked.config.as_ref().unwrap();
+        assert_eq!(cfg.bet_amount, 45);
+        assert_eq!(checked.source("bet_amount").unwrap().layer, Layer::Cli);
+        assert_eq!(cfg.currency_name, "p");
+        assert_eq!(checked.source("currency_name").unwrap().layer, Layer::Project);
+        assert_eq!(cfg.rules.decks, 2);
+        assert_eq!(checked.source("rules.decks").unwrap().layer, Layer::User);
+        assert_eq!(cfg.player_starting_bank, crate::config::GameConfig::default().player_starting_bank);
+        assert!(checked.source("player_starting_bank").is_none());
+    }
+
+    #[test]
+    fn env_beats_files_and_loses_to_flags() {
+        let layers = |flag: bool| {
+            let mut inputs = vec![
+                file(Layer::Project, "env", "bet_amount = 30\n[rules]\ndecks = 4\n"),
+                Input::Values { layer: Layer::Env, entries: env_entries(vars(&[("VEINTIUNA_RULES__DECKS", "1")])) },
+            ];
+            if flag {
+                inputs.push(entry(Layer::Cli, "rules.decks", 8));
+            }
+            let checked = check_layers(&inputs);
+            for input in &checked.files {
+                let _ = fs::remove_file(input);
+            }
+            checked
+        };
+
+        let checked = layers(false);
+        assert_eq!(checked.config.as_ref().unwrap().rules.decks, 1);
+        assert_eq!(checked.source("rules.decks").unwrap().origin, "VEINTIUNA_RULES__DECKS");
+
+        let checked = layers(true);
+        assert_eq!(checked.config.as_ref().unwrap().rules.decks, 8);
+        assert_eq!(checked.source("rules.decks").unwrap().layer, Layer::Cli);
+    }
+}
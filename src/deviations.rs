@@ -0,0 +1,302 @@
+use crate::card::{Card, hand_value};
+use crate::game_action::GameAction;
+use crate::strategy::{basic_strategy, is_soft};
+use crate::t;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Which player hands an index play applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandKey {
+    Hard(u8),
+    Soft(u8),
+    /// pair of cards with this value (11 = aces)
+    Pair(u8),
+}
+
+impl HandKey {
+    /// Parses "16" (hard), "A7" (soft 18) or "10,10" (pair).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let value = |t: &str| match t.trim() {
+            "A" | "a" => Some(11),
+            t => t.parse::<u8>().ok().filter(|v| (2..=10).contains(v)),
+        };
+        if let Some((a, b)) = s.split_once(',') {
+            let (a, b) = (value(a)?, value(b)?);
+            return (a == b).then_some(HandKey::Pair(a));
+        }
+        if let Some(rest) = s.strip_prefix(['A', 'a']) {
+            let other = rest.trim().parse::<u8>().ok().filter(|v| (2..=9).contains(v))?;
+            return Some(HandKey::Soft(11 + other));
+        }
+        s.parse::<u8>().ok().filter(|v| (4..=21).contains(v)).map(HandKey::Hard)
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            HandKey::Hard(total) => t!("hand.hard", total = total),
+            HandKey::Soft(total) => t!("hand.soft", total = total),
+            HandKey::Pair(11) => "A,A".to_string(),
+            HandKey::Pair(v) => format!("{},{}", v, v),
+        }
+    }
+}
+
+/// One count-dependent change to basic strategy.
+#[derive(Debug, Clone)]
+pub struct IndexPlay {
+    pub hand: HandKey,
+    /// dealer up-card value (11 = ace)
+    pub up: u8,
+    pub index: f32,
+    /// applies below `index` instead of at or above it
+    pub below: bool,
+    pub action: GameAction,
+}
+
+impl IndexPlay {
+    const fn at_or_above(hand: HandKey, up: u8, index: f32, action: GameAction) -> Self {
+        Self { hand, up, index, below: false, action }
+    }
+
+    const fn under(hand: HandKey, up: u8, index: f32, action: GameAction) -> Self {
+        Self { hand, up, index, below: true, action }
+    }
+
+    fn triggers(&self, true_count: f32) -> bool {
+        if self.below { true_count < self.index } else { true_count >= self.index }
+    }
+
+    pub fn describe(&self) -> String {
+        let up = if self.up == 11 { "A".to_string() } else { self.up.to_string() };
+        let cmp = if self.below { "<" } else { "≥" };
+        format!("{} vs {} (TC {} {:+})", self.hand.label(), up, cmp, self.index)
+    }
+}
+
+/// Index plays layered on top of basic strategy.
+#[derive(Debug, Clone)]
+pub struct Deviations {
+    pub plays: Vec<IndexPlay>,
+    /// take insurance at or above this true count
+    pub insurance_index: f32,
+}
+
+#[derive(Deserialize)]
+struct DeviationFile {
+    insurance_index: Option<f32>,
+    #[serde(default)]
+    deviation: Vec<DeviationEntry>,
+}
+
+#[derive(Deserialize)]
+struct DeviationEntry {
+    hand: String,
+    up: UpCard,
+    index: f32,
+    #[serde(default)]
+    below: bool,
+    action: GameAction,
+}
+
+/// Up-card written as a number (`10`) or a string (`"A"`).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UpCard {
+    Value(u8),
+    Name(String),
+}
+
+impl UpCard {
+    fn value(&self) -> Option<u8> {
+        match self {
+            UpCard::Value(v) => Some(*v),
+            UpCard::Name(s) if s.trim().eq_ignore_ascii_case("A") => Some(11),
+            UpCard::Name(s) => s.trim().parse().ok(),
+        }
+        .filter(|v| (2..=11).contains(v))
+    }
+}
+
+impl Default for Deviations {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Deviations {
+    /// Fab 4 surrenders and the Illustrious 18 (insurance is `insurance_index`).
+    pub fn builtin() -> Self {
+        use GameAction::*;
+        use HandKey::*;
+        let plays = vec![
+            // Fab 4
+            IndexPlay::at_or_above(Hard(14), 10, 3.0, Surrender),
+            IndexPlay::at_or_above(Hard(15), 10, 0.0, Surrender),
+            IndexPlay::at_or_above(Hard(15), 9, 2.0, Surrender),
+            IndexPlay::at_or_above(Hard(15), 11, 1.0, Surrender),
+            // Illustrious 18
+            IndexPlay::at_or_above(Hard(16), 10, 0.0, Stand),
+            IndexPlay::at_or_above(Hard(15), 10, 4.0, Stand),
+            IndexPlay::at_or_above(Pair(10), 5, 5.0, Split),
+            IndexPlay::at_or_above(Pair(10), 6, 4.0, Split),
+            IndexPlay::at_or_above(Hard(10), 10, 4.0, DoubleDown),
+            IndexPlay::at_or_above(Hard(12), 3, 2.0, Stand),
+            IndexPlay::at_or_above(Hard(12), 2, 3.0, Stand),
+            IndexPlay::at_or_above(Hard(11), 11, 1.0, DoubleDown),
+            IndexPlay::at_or_above(Hard(9), 2, 1.0, DoubleDown),
+            IndexPlay::at_or_above(Hard(10), 11, 4.0, DoubleDown),
+            IndexPlay::at_or_above(Hard(9), 7, 3.0, DoubleDown),
+            IndexPlay::at_or_above(Hard(16), 9, 5.0, Stand),
+            IndexPlay::under(Hard(13), 2, -1.0, Hit),
+            IndexPlay::under(Hard(12), 4, 0.0, Hit),
+            IndexPlay::under(Hard(12), 5, -2.0, Hit),
+            IndexPlay::under(Hard(12), 6, -1.0, Hit),
+            IndexPlay::under(Hard(13), 3, -2.0, Hit),
+        ];
+        Self { plays, insurance_index: 3.0 }
+    }
+
+    /// Built-in plays plus the user's entries from `path`, which take priority.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut deviations = Self::builtin();
+        if !path.exists() {
+            return Ok(deviations);
+        }
+        let file: DeviationFile = toml::from_str(&fs::read_to_string(path)?)?;
+
+        let mut user = Vec::new();
+        for (i, entry) in file.deviation.into_iter().enumerate() {
+            let hand = HandKey::parse(&entry.hand)
+                .ok_or_else(|| anyhow::anyhow!(t!("deviations.bad_hand", index = i + 1, hand = entry.hand)))?;
+            let up = entry
+                .up
+                .value()
+                .ok_or_else(|| anyhow::anyhow!(t!("deviations.bad_up", index = i + 1)))?;
+            user.push(IndexPlay { hand, up, index: entry.index, below: entry.below, action: entry.action });
+        }
+        user.append(&mut deviations.plays);
+        deviations.plays = user;
+        if let Some(index) = file.insurance_index {
+            deviations.insurance_index = index;
+        }
+        Ok(deviations)
+    }
+
+    pub fn take_insurance(&self, true_count: f32) -> bool {
+        true_count >= self.insurance_index
+    }
+
+    /// First index play that fires at `true_count` and is legal right now.
+    /// Where basic strategy surrenders (16 vs 9 and 10), only a surrender
+    /// index play applies; the Illustrious 18 stands are for tables that
+    /// do not offer it.
+    pub fn lookup(&self, hand: &[Card], dealer_up: Card, legal: &[GameAction], true_count: f32) -> Option<&IndexPlay> {
+        let up = dealer_up.value();
+        let basic = basic_strategy(hand, dealer_up, legal);
+        let total = hand_value(hand);
+        let pair = legal.contains(&GameAction::Split).then(|| hand[0].value());
+
+        self.plays.iter().find(|play| {
+            let matches_hand = match play.hand {
+                HandKey::Pair(v) => pair == Some(v),
+                // a pair that basic strategy splits is never played as a total
+                _ if basic == GameAction::Split => false,
+                HandKey::Soft(t) => is_soft(hand) && total == t,
+                HandKey::Hard(t) => !is_soft(hand) && total == t,
+            };
+            let outranked = basic == GameAction::Surrender && play.action != GameAction::Surrender;
+            matches_hand && !outranked && play.up == up && play.triggers(true_count) && legal.contains(&play.action)
+        })
+    }
+}
+
+/// Basic strategy adjusted by index plays for the current true count.
+pub fn count_strategy(
+    hand: &[Card],
+    dealer_up: Card,
+    legal: &[GameAction],
+    true_count: f32,
+    deviations: &Deviations,
+) -> GameAction {
+    deviations
+        .lookup(hand, dealer_up, legal, true_count)
+        .map(|play| play.action)
+        .unwrap_or_else(|| basic_strategy(hand, dealer_up, legal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::{Rank, Suit};
+    use GameAction::*;
+
+    fn hand(ranks: &[Rank]) -> Vec<Card> {
+        ranks.iter().map(|&rank| Card(rank, Suit::Spades)).collect()
+    }
+
+    fn up(value: u8) -> Card {
+        let rank = match value {
+            9 => Rank::Nine,
+            10 => Rank::Ten,
+            _ => Rank::Ace,
+        };
+        Card(rank, Suit::Hearts)
+    }
+
+    /// The action an index play changes the hand to, if one fires.
+    fn deviation(cards: &[Card], dealer: u8, legal: &[GameAction], true_count: f32) -> Option<GameAction> {
+        Deviations::builtin().lookup(cards, up(dealer), legal, true_count).map(|play| play.action)
+    }
+
+    const WITH_SURRENDER: &[GameAction] = &[Hit, Stand, DoubleDown, Surrender];
+    const NO_SURRENDER: &[GameAction] = &[Hit, Stand, DoubleDown];
+
+    #[test]
+    fn surrender_outranks_the_stand_on_16_vs_10() {
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        for tc in [-1.0, 0.0, 5.0] {
+            assert_eq!(deviation(&sixteen, 10, WITH_SURRENDER, tc), None, "TC {}", tc);
+            assert_eq!(count_strategy(&sixteen, up(10), WITH_SURRENDER, tc, &Deviations::builtin()), Surrender);
+        }
+        assert_eq!(deviation(&sixteen, 10, NO_SURRENDER, -1.0), None);
+        assert_eq!(deviation(&sixteen, 10, NO_SURRENDER, 0.0), Some(Stand));
+        assert_eq!(deviation(&sixteen, 10, NO_SURRENDER, 5.0), Some(Stand));
+    }
+
+    #[test]
+    fn surrender_outranks_the_stand_on_16_vs_9() {
+        let sixteen = hand(&[Rank::Nine, Rank::Seven]);
+        assert_eq!(count_strategy(&sixteen, up(9), WITH_SURRENDER, 5.0, &Deviations::builtin()), Surrender);
+        assert_eq!(deviation(&sixteen, 9, NO_SURRENDER, 4.9), None);
+        assert_eq!(deviation(&sixteen, 9, NO_SURRENDER, 5.0), Some(Stand));
+    }
+
+    #[test]
+    fn fab_4_thresholds() {
+        let fourteen = hand(&[Rank::Ten, Rank::Four]);
+        let fifteen = hand(&[Rank::Ten, Rank::Five]);
+        let cases = [(&fourteen, 10, 3.0), (&fifteen, 10, 0.0), (&fifteen, 9, 2.0), (&fifteen, 11, 1.0)];
+        for (cards, dealer, index) in cases {
+            assert_eq!(deviation(cards, dealer, WITH_SURRENDER, index), Some(Surrender), "vs {} at {}", dealer, index);
+            assert_ne!(deviation(cards, dealer, WITH_SURRENDER, index - 1.0), Some(Surrender), "vs {}", dealer);
+            // without surrender the index play cannot fire
+            assert_ne!(deviation(cards, dealer, NO_SURRENDER, index), Some(Surrender));
+        }
+    }
+
+    #[test]
+    fn user_plays_take_priority() {
+        let path = std::env::temp_dir().join(format!("veintiuna-deviations-{}.toml", std::process::id()));
+        fs::write(&path, "insurance_index = 2.5\n[[deviation]]\nhand = \"16\"\nup = 10\nindex = 0\naction = \"hit\"\n")
+            .unwrap();
+        let deviations = Deviations::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        let play = deviations.lookup(&sixteen, up(10), NO_SURRENDER, 1.0).unwrap();
+        assert_eq!(play.action, Hit);
+        assert!(deviations.take_insurance(2.5) && !deviations.take_insurance(2.4));
+    }
+}
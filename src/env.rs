@@ -0,0 +1,197 @@
+use crate::card::hand_value;
+use crate::config::GameConfig;
+use crate::game_action::GameAction;
+use crate::round::{Round, Wager};
+use crate::rules::Rules;
+use crate::shoe::Shoe;
+use crate::strategy::is_soft;
+use crate::t;
+use serde::Serialize;
+
+/// Actions in the order of `Observation::action_mask`.
+pub const ACTIONS: [GameAction; 5] =
+    [GameAction::Hit, GameAction::Stand, GameAction::DoubleDown, GameAction::Split, GameAction::Surrender];
+
+/// Each episode is played for ten chips so a surrender loses exactly half
+/// and 3:2 and 6:5 blackjacks pay whole chips; rewards are reported in units
+/// of the base bet.
+const BASE_BET: u32 = 10;
+
+/// What the agent sees of the hand to act.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Observation {
+    pub total: u8,
+    pub soft: bool,
+    /// up-card value, 2..=11 (ace = 11)
+    pub dealer_up: u8,
+    pub true_count: f32,
+    /// legal actions, indexed like `ACTIONS`; all false once done
+    pub action_mask: [bool; 5],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Step {
+    pub observation: Observation,
+    /// net result in base bets, paid on the step that ends the episode
+    pub reward: f32,
+    pub done: bool,
+}
+
+/// Gym-style environment over the real round engine: one episode is one
+/// round for a single spot. The shoe and its count carry over between
+/// episodes until a new seed is given; insurance is always declined.
+pub struct BlackjackEnv {
+    rules: Rules,
+    player_edge: f32,
+    shoe: Shoe,
+    round: Option<Round>,
+}
+
+impl BlackjackEnv {
+    pub fn new(cfg: &GameConfig, seed: u64) -> Self {
+        Self {
+            rules: cfg.rules.clone(),
+            player_edge: cfg.player_edge,
+            shoe: Shoe::new(cfg.rules.decks, cfg.rules.penetration, seed),
+            round: None,
+        }
+    }
+
+    /// Deals a new episode, starting a fresh shoe when `seed` is given. The
+    /// step is already done when the deal settles it (blackjacks).
+    pub fn reset(&mut self, seed: Option<u64>) -> Step {
+        if let Some(seed) = seed {
+            self.shoe = Shoe::new(self.rules.decks, self.rules.penetration, seed);
+        } else if self.shoe.needs_shuffle() {
+            self.shoe.shuffle();
+        }
+        let mut round = Round::deal(&mut self.shoe, &[Wager { bet: BASE_BET, bankroll: u32::MAX }], &self.rules);
+        while round.pending_insurance().is_some() {
+            round.resolve_insurance(&mut self.shoe, false);
+        }
+        self.round = Some(round);
+        self.finish_if_done()
+    }
+
+    pub fn step(&mut self, action: GameAction) -> anyhow::Result<Step> {
+        let Some(round) = self.round.as_mut().filter(|r| r.active().is_some()) else {
+            anyhow::bail!(t!("env.episode_over"));
+        };
+        round.apply(&mut self.shoe, action)?;
+        Ok(self.finish_if_done())
+    }
+
+    /// Plays the dealer once no hand is left to act and pays the reward.
+    fn finish_if_done(&mut self) -> Step {
+        let Some(round) = self.round.as_mut() else {
+            return Step { observation: self.observation(), reward: 0.0, done: true };
+        };
+        // nobody reads the event log here
+        round.drain_events();
+        if round.active().is_some() {
+            return Step { observation: self.observation(), reward: 0.0, done: false };
+        }
+        round.play_dealer(&mut self.shoe);
+        round.drain_events();
+        let reward = round.net(0, self.player_edge) as f32 / BASE_BET as f32;
+        Step { observation: self.observation(), reward, done: true }
+    }
+
+    /// The hand to act, or the last hand played once the episode is done.
+    pub fn observation(&self) -> Observation {
+        let Some(round) = &self.round else {
+            return Observation {
+                total: 0,
+                soft: false,
+                dealer_up: 0,
+                true_count: self.shoe.true_count(),
+                action_mask: [false; 5],
+            };
+        };
+        let hand = round.active_hand().or_else(|| round.spots[0].hands.last()).unwrap();
+        let legal = round.legal_actions();
+        Observation {
+            total: hand_value(&hand.cards),
+            soft: is_soft(&hand.cards),
+            dealer_up: round.dealer_up_card().value(),
+            true_count: self.shoe.true_count(),
+            action_mask: ACTIONS.map(|a| legal.contains(&a)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An environment whose first episode (from `seed` on) is not settled by the deal.
+    fn open_episode(from: u64) -> (BlackjackEnv, Step) {
+        let mut env = BlackjackEnv::new(&GameConfig::default(), from);
+        for seed in from..from + 100 {
+            let step = env.reset(Some(seed));
+            if !step.done {
+                return (env, step);
+            }
+        }
+        panic!("every deal was a blackjack");
+    }
+
+    #[test]
+    fn observation_describes_the_hand_to_act() {
+        let env = BlackjackEnv::new(&GameConfig::default(), 1);
+        assert_eq!(env.observation().action_mask, [false; 5]);
+
+        let (env, step) = open_episode(1);
+        let round = env.round.as_ref().unwrap();
+        let cards = &round.spots[0].hands[0].cards;
+        let obs = step.observation;
+        assert_eq!((obs.total, obs.soft), (hand_value(cards), is_soft(cards)));
+        assert_eq!(obs.dealer_up, round.dealer_up_card().value());
+        assert!((2..=11).contains(&obs.dealer_up));
+        assert_eq!(obs.true_count, env.shoe.true_count());
+        assert_eq!(obs.action_mask, ACTIONS.map(|a| round.legal_actions().contains(&a)));
+        assert!(obs.action_mask[0] && obs.action_mask[1]);
+        assert_eq!(step.reward, 0.0);
+    }
+
+    #[test]
+    fn a_seed_repeats_the_episode() {
+        let mut env = BlackjackEnv::new(&GameConfig::default(), 0);
+        let first = env.reset(Some(42));
+        env.step(GameAction::Stand).ok();
+        assert_eq!(env.reset(Some(42)), first);
+    }
+
+    #[test]
+    fn stand_ends_the_episode_with_the_round_result() {
+        for from in [1, 200, 400] {
+            let (mut env, _) = open_episode(from);
+            let step = env.step(GameAction::Stand).unwrap();
+            assert!(step.done);
+            assert_eq!(step.observation.action_mask, [false; 5]);
+            let net = env.round.as_ref().unwrap().net(0, env.player_edge);
+            assert_eq!(step.reward, net as f32 / BASE_BET as f32);
+            assert_eq!(step.reward.signum(), (net as f32).signum());
+            assert!(env.step(GameAction::Hit).is_err());
+        }
+    }
+
+    #[test]
+    fn busting_loses_the_bet() {
+        let (mut env, mut step) = open_episode(5);
+        while !step.done {
+            step = env.step(GameAction::Hit).unwrap();
+        }
+        assert!(step.observation.total > 21);
+        assert_eq!(step.reward, -1.0);
+    }
+
+    #[test]
+    fn surrender_loses_half_the_bet() {
+        let (mut env, step) = open_episode(9);
+        assert!(step.observation.action_mask[4]);
+        let step = env.step(GameAction::Surrender).unwrap();
+        assert!(step.done);
+        assert_eq!(step.reward, -0.5);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::blackjack::HandOutcome;
+use crate::card::Card;
+use crate::game_action::GameAction;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened at the table, in the order it happened. Only
+/// face-up information is carried: the hole card first appears in `Reveal`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TableEvent {
+    /// names of the spots in play this round, in seat order
+    Table { seats: Vec<String> },
+    Shuffle { decks: u8 },
+    /// opening two cards of every spot and the dealer's up-card
+    Deal { hands: Vec<Vec<Card>>, dealer_up: Card },
+    Insurance { spot: usize, take: bool },
+    /// the hand's cards and total after the action
+    Action { spot: usize, hand: usize, action: GameAction, cards: Vec<Card>, total: u8 },
+    Reveal { hole: Card, total: u8 },
+    DealerDraw { card: Card, total: u8 },
+    Settlement { dealer: Vec<Card>, dealer_total: u8, spots: Vec<SpotSettlement> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotSettlement {
+    pub outcomes: Vec<HandOutcome>,
+    pub net: i64,
+}
+
+/// Receives the table's events as they happen (spectator feeds and the like).
+pub trait EventSink {
+    fn publish(&mut self, event: &TableEvent);
+}
@@ -0,0 +1,380 @@
+//! C ABI for embedding the engine; the declarations live in
+//! `ffi/veintiuna.h` and must change together with this file. Every call
+//! returns a status code instead of unwinding into C.
+
+use crate::card::Card;
+use crate::config::GameConfig;
+use crate::env::ACTIONS;
+use crate::headless::{HeadlessSession, Phase};
+use crate::t;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::ptr;
+
+pub const VT_OK: i32 = 0;
+/// a required pointer was null
+pub const VT_ERR_NULL: i32 = 1;
+/// the call does not fit the table's phase (see `vt_last_error`)
+pub const VT_ERR_PHASE: i32 = 2;
+/// bet outside the table limits or the bank
+pub const VT_ERR_BET: i32 = 3;
+/// action not legal for the hand, or an unknown action code
+pub const VT_ERR_ACTION: i32 = 4;
+/// the engine panicked; the table should be destroyed
+pub const VT_ERR_INTERNAL: i32 = 5;
+
+pub const VT_MAX_CARDS: usize = 16;
+pub const VT_MAX_HANDS: usize = 8;
+
+/// Opaque handle: one seat against the dealer.
+pub struct VtTable {
+    session: HeadlessSession,
+    last_error: CString,
+}
+
+#[repr(C)]
+pub struct VtHand {
+    /// rank codes: 2..=10, 11 = J, 12 = Q, 13 = K, 14 = A
+    pub cards: [u8; VT_MAX_CARDS],
+    pub card_count: u8,
+    pub total: u8,
+    pub soft: bool,
+    pub doubled: bool,
+    pub surrendered: bool,
+    pub bet: u32,
+    /// `VT_OUTCOME_*` once settled, -1 before
+    pub outcome: i32,
+}
+
+#[repr(C)]
+pub struct VtState {
+    /// `VT_PHASE_*`
+    pub phase: i32,
+    pub bank: u32,
+    pub min_bet: u32,
+    pub max_bet: u32,
+    /// rank codes; 0 for the face-down hole card
+    pub dealer_cards: [u8; VT_MAX_CARDS],
+    pub dealer_count: u8,
+    /// total of the face-up cards
+    pub dealer_total: u8,
+    /// number of hands in play; only the first `VT_MAX_HANDS` are filled in
+    pub hand_count: u8,
+    /// index of the hand to act, -1 when none
+    pub active_hand: i8,
+    pub hands: [VtHand; VT_MAX_HANDS],
+    /// bit `1 << VT_ACTION_*` for every legal action
+    pub legal_actions: u32,
+    /// price of insurance while it is offered, else 0
+    pub insurance_cost: u32,
+}
+
+fn rank_code(card: Card) -> u8 {
+    card.0 as u8 + 2
+}
+
+fn fill(codes: &mut [u8; VT_MAX_CARDS], cards: impl Iterator<Item = u8>) -> u8 {
+    let mut count = 0;
+    for (slot, code) in codes.iter_mut().zip(cards) {
+        *slot = code;
+        count += 1;
+    }
+    count
+}
+
+/// Runs `f` on the table, turning errors and panics into status codes.
+///
+/// # Safety
+/// `table` is null or a live handle not used from another thread meanwhile.
+unsafe fn with_table(table: *mut VtTable, f: impl FnOnce(&mut VtTable) -> Result<(), (i32, String)>) -> i32 {
+    // SAFETY: see above.
+    let Some(table) = (unsafe { table.as_mut() }) else {
+        return VT_ERR_NULL;
+    };
+    let (status, message) = match panic::catch_unwind(AssertUnwindSafe(|| f(table))) {
+        Ok(Ok(())) => (VT_OK, String::new()),
+        Ok(Err(error)) => error,
+        Err(_) => (VT_ERR_INTERNAL, t!("ffi.internal")),
+    };
+    table.last_error = CString::new(message).unwrap_or_default();
+    status
+}
+
+fn new_table(config: GameConfig, seed: u64) -> *mut VtTable {
+    let session = HeadlessSession::new(config, seed);
+    Box::into_raw(Box::new(VtTable { session, last_error: CString::default() }))
+}
+
+/// Creates a table with the default rules. Free it with `vt_table_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn vt_table_new(seed: u64) -> *mut VtTable {
+    panic::catch_unwind(|| new_table(GameConfig::default(), seed)).unwrap_or(ptr::null_mut())
+}
+
+/// Creates a table with the rules from a `game_config.toml`; null if the
+/// file cannot be read.
+///
+/// # Safety
+/// `config_path` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_table_new_with_config(config_path: *const c_char, seed: u64) -> *mut VtTable {
+    if config_path.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: checked for null; the caller guarantees NUL termination.
+    let path = unsafe { CStr::from_ptr(config_path) }.to_string_lossy().into_owned();
+    panic::catch_unwind(|| match GameConfig::load(Path::new(&path)) {
+        Ok(config) => new_table(config, seed),
+        Err(_) => ptr::null_mut(),
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// # Safety
+/// `table` must come from `vt_table_new*` and not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_table_free(table: *mut VtTable) {
+    if !table.is_null() {
+        // SAFETY: the pointer was made by `Box::into_raw` in `new_table`.
+        drop(unsafe { Box::from_raw(table) });
+    }
+}
+
+/// Places the bet and deals the next round.
+///
+/// # Safety
+/// `table` must be a live handle from `vt_table_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_place_bet(table: *mut VtTable, amount: u32) -> i32 {
+    let bet = |t: &mut VtTable| {
+        let phase = t.session.phase();
+        t.session.bet(amount).map_err(|e| {
+            let status = if phase == Phase::Betting { VT_ERR_BET } else { VT_ERR_PHASE };
+            (status, e.to_string())
+        })
+    };
+    // SAFETY: forwarded from the caller.
+    unsafe { with_table(table, bet) }
+}
+
+/// # Safety
+/// `table` must be a live handle from `vt_table_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_insurance(table: *mut VtTable, take: bool) -> i32 {
+    // SAFETY: forwarded from the caller.
+    unsafe { with_table(table, |t| t.session.insurance(take).map_err(|e| (VT_ERR_PHASE, e.to_string()))) }
+}
+
+/// Applies a `VT_ACTION_*` to the active hand.
+///
+/// # Safety
+/// `table` must be a live handle from `vt_table_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_apply_action(table: *mut VtTable, action: i32) -> i32 {
+    let act = |t: &mut VtTable| {
+        let Some(&action) = usize::try_from(action).ok().and_then(|i| ACTIONS.get(i)) else {
+            return Err((VT_ERR_ACTION, t!("ffi.unknown_action", action = action)));
+        };
+        if t.session.phase() != Phase::PlayerTurn {
+            return Err((VT_ERR_PHASE, t!("error.action_phase", phase = format!("{:?}", t.session.phase()))));
+        }
+        t.session.act(action).map_err(|e| (VT_ERR_ACTION, e.to_string()))
+    };
+    // SAFETY: forwarded from the caller.
+    unsafe { with_table(table, act) }
+}
+
+/// Net result of the round just finished. The dealer plays and the bank is
+/// paid as soon as the last decision is made; this only reads the result.
+///
+/// # Safety
+/// `table` must be a live handle and `net` writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_settle(table: *mut VtTable, net: *mut i64) -> i32 {
+    if net.is_null() {
+        return VT_ERR_NULL;
+    }
+    let settle = |t: &mut VtTable| match t.session.state().last_result {
+        Some(result) => {
+            // SAFETY: checked for null above.
+            unsafe { net.write(result.net) };
+            Ok(())
+        }
+        None => Err((VT_ERR_PHASE, t!("ffi.no_result"))),
+    };
+    // SAFETY: forwarded from the caller.
+    unsafe { with_table(table, settle) }
+}
+
+/// Copies the visible table into `out`.
+///
+/// # Safety
+/// `table` must be a live handle and `out` writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_get_state(table: *mut VtTable, out: *mut VtState) -> i32 {
+    if out.is_null() {
+        return VT_ERR_NULL;
+    }
+    let copy = |t: &mut VtTable| {
+        let state = t.session.state();
+        let mut view = VtState {
+            phase: state.phase as i32,
+            bank: state.bank,
+            min_bet: state.min_bet,
+            max_bet: state.max_bet,
+            dealer_cards: [0; VT_MAX_CARDS],
+            dealer_count: 0,
+            dealer_total: state.dealer_total,
+            hand_count: state.hands.len() as u8,
+            active_hand: state.active_hand.map_or(-1, |i| i as i8),
+            hands: std::array::from_fn(|_| VtHand {
+                cards: [0; VT_MAX_CARDS],
+                card_count: 0,
+                total: 0,
+                soft: false,
+                doubled: false,
+                surrendered: false,
+                bet: 0,
+                outcome: -1,
+            }),
+            legal_actions: 0,
+            insurance_cost: state.insurance_cost.unwrap_or(0),
+        };
+        view.dealer_count = fill(&mut view.dealer_cards, state.dealer.iter().map(|c| c.map_or(0, rank_code)));
+        for (slot, hand) in view.hands.iter_mut().zip(&state.hands) {
+            slot.card_count = fill(&mut slot.cards, hand.cards.iter().map(|&c| rank_code(c)));
+            slot.total = hand.total;
+            slot.soft = hand.soft;
+            slot.doubled = hand.doubled;
+            slot.surrendered = hand.surrendered;
+            slot.bet = hand.bet;
+            slot.outcome = hand.outcome.map_or(-1, |o| o as i32);
+        }
+        for (bit, action) in ACTIONS.iter().enumerate() {
+            if state.allowed_actions.contains(action) {
+                view.legal_actions |= 1 << bit;
+            }
+        }
+        // SAFETY: checked for null above.
+        unsafe { out.write(view) };
+        Ok(())
+    };
+    // SAFETY: forwarded from the caller.
+    unsafe { with_table(table, copy) }
+}
+
+/// Message for the last failed call on this table, empty after a success.
+/// Valid until the next call on the same table.
+///
+/// # Safety
+/// `table` must be a live handle from `vt_table_new*`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vt_last_error(table: *const VtTable) -> *const c_char {
+    // SAFETY: the caller passes a live handle or null.
+    match unsafe { table.as_ref() } {
+        Some(table) => table.last_error.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::MaybeUninit;
+
+    const PHASE_INSURANCE: i32 = Phase::Insurance as i32;
+    const PHASE_PLAYER_TURN: i32 = Phase::PlayerTurn as i32;
+    const ACTION_STAND: i32 = 1;
+
+    fn state(table: *mut VtTable) -> VtState {
+        let mut out = MaybeUninit::<VtState>::uninit();
+        // SAFETY: `table` is live and `vt_get_state` fills `out` when it returns VT_OK.
+        assert_eq!(unsafe { vt_get_state(table, out.as_mut_ptr()) }, VT_OK);
+        unsafe { out.assume_init() }
+    }
+
+    fn last_error(table: *mut VtTable) -> String {
+        // SAFETY: `table` is live, so the message is a valid C string.
+        unsafe { CStr::from_ptr(vt_last_error(table)) }.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn null_pointers_are_refused() {
+        let mut net = 0i64;
+        // SAFETY: the only handle is made by `vt_table_new` and freed once.
+        unsafe {
+            assert_eq!(vt_place_bet(ptr::null_mut(), 10), VT_ERR_NULL);
+            assert_eq!(vt_insurance(ptr::null_mut(), false), VT_ERR_NULL);
+            assert_eq!(vt_apply_action(ptr::null_mut(), ACTION_STAND), VT_ERR_NULL);
+            assert_eq!(vt_settle(ptr::null_mut(), &mut net), VT_ERR_NULL);
+            assert!(vt_last_error(ptr::null()).is_null());
+            assert!(vt_table_new_with_config(ptr::null(), 1).is_null());
+            vt_table_free(ptr::null_mut());
+
+            let table = vt_table_new(1);
+            assert!(!table.is_null());
+            assert_eq!(vt_get_state(table, ptr::null_mut()), VT_ERR_NULL);
+            assert_eq!(vt_settle(table, ptr::null_mut()), VT_ERR_NULL);
+            vt_table_free(table);
+        }
+    }
+
+    #[test]
+    fn bad_calls_report_their_status() {
+        let table = vt_table_new(3);
+        let mut net = 0i64;
+        // SAFETY: `table` is freed once, at the end.
+        unsafe {
+            assert_eq!(vt_settle(table, &mut net), VT_ERR_PHASE);
+            assert_eq!(vt_apply_action(table, ACTION_STAND), VT_ERR_PHASE);
+            assert!(!last_error(table).is_empty());
+            assert_eq!(vt_place_bet(table, 0), VT_ERR_BET);
+            assert_eq!(vt_place_bet(table, u32::MAX), VT_ERR_BET);
+            assert_eq!(vt_apply_action(table, -1), VT_ERR_ACTION);
+            assert_eq!(vt_apply_action(table, ACTIONS.len() as i32), VT_ERR_ACTION);
+
+            let min_bet = state(table).min_bet;
+            assert_eq!(vt_place_bet(table, min_bet), VT_OK);
+            assert!(last_error(table).is_empty());
+            assert_eq!(vt_place_bet(table, min_bet), VT_ERR_PHASE);
+            vt_table_free(table);
+        }
+    }
+
+    #[test]
+    fn deal_stand_settle_and_free() {
+        let table = vt_table_new(5);
+        // SAFETY: `table` is freed once, at the end.
+        unsafe {
+            let start = state(table);
+            let mut stood = false;
+            while !stood {
+                assert_eq!(vt_place_bet(table, start.min_bet), VT_OK);
+                while state(table).phase == PHASE_INSURANCE {
+                    assert_eq!(vt_insurance(table, false), VT_OK);
+                }
+                while state(table).phase == PHASE_PLAYER_TURN {
+                    let before = state(table);
+                    assert_eq!(before.active_hand, 0);
+                    assert_ne!(before.legal_actions & (1 << ACTION_STAND), 0);
+                    assert_eq!(before.dealer_cards[0], 0, "the hole card stays hidden");
+                    assert_eq!(vt_apply_action(table, ACTION_STAND), VT_OK);
+                    stood = true;
+                }
+            }
+            let mut net = 0i64;
+            assert_eq!(vt_settle(table, &mut net), VT_OK);
+            let after = state(table);
+            assert_eq!(after.phase, Phase::Betting as i32);
+            assert_eq!(after.active_hand, -1);
+            assert_eq!(after.hand_count, 1);
+            assert_eq!(after.hands[0].card_count, 2);
+            assert_ne!(after.hands[0].outcome, -1);
+            assert_ne!(after.dealer_cards[0], 0);
+            assert_eq!(after.legal_actions, 0);
+            assert!(net.abs() <= i64::from(start.min_bet) * 2);
+            vt_table_free(table);
+        }
+    }
+}
@@ -1,276 +1,504 @@
 use crate::config::GameConfig;
-use crate::card::{build_deck, shuffle_deck, hand_value, is_blackjack, print_hand};
-use crate::menu::{MenuChoice, PostGameChoice, display_help, get_user_choice};
-use crate::game_action::PlayerActionResult;
-use crate::blackjack::{player_turn, dealer_turn};
-use dialoguer::{Select, theme::ColorfulTheme};
-
-pub fn run_game(cfg: &GameConfig) -> anyhow::Result<()> {
-    println!("現在の残高: {}{}", cfg.player_starting_bank, cfg.currency_name);
-    println!("ベット額: {}{}", cfg.bet_amount, cfg.currency_name);
-    println!();
+use crate::card::{hand_value, print_hand};
+use crate::menu::{BetChoice, MenuChoice, PostGameChoice, display_help, get_user_choice, pick_bet, pick_preset, print_rules};
+use crate::blackjack::{Checkpoint, bot_insurance, bot_turn, insurance_turn, player_turn};
+use crate::player::PlayerState;
+use crate::round::{Round, Wager};
+use crate::shoe::Shoe;
+use crate::table::Table;
+use crate::trainer::run_trainer;
+use crate::prompt;
+use crate::session::{self, Session};
+use crate::settings::edit_settings;
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+/// A round and, for each spot, the seat that plays it. Saved after every
+/// action while the round is in progress.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PlayedRound {
+    pub round: Round,
+    pub seats: Vec<usize>,
+}
+
+/// Saves a round in progress, given the seat of each spot, the round and the
+/// shoe.
+pub type SaveRound<'a> = dyn FnMut(&[usize], &Round, &Shoe) -> anyhow::Result<()> + 'a;
 
-    let mut deck = build_deck();
-    shuffle_deck(&mut deck);
-
-    let mut player_hands = Vec::new();
-    let mut dealer_hand = Vec::new();
-
-    // initial deal: player, dealer, player, dealer
-    let mut initial_hand = Vec::new();
-    initial_hand.push(deck.pop().unwrap());
-    dealer_hand.push(deck.pop().unwrap());
-    initial_hand.push(deck.pop().unwrap());
-    dealer_hand.push(deck.pop().unwrap());
-
-    player_hands.push(initial_hand);
-
-    print_hand("ディーラー", &dealer_hand, true);
-
-    // Check for dealer blackjack
-    let dealer_has_blackjack = is_blackjack(&dealer_hand);
-    if dealer_has_blackjack {
-        println!("\nディーラーがブラックジャック！");
-        print_hand("ディーラー", &dealer_hand, false);
-        
-        // Check if player also has blackjack (only applies to first hand before any splits)
-        let player_has_blackjack = player_hands.len() == 1 && is_blackjack(&player_hands[0]);
-        
-        if player_has_blackjack {
-            println!("プレイヤーもブラックジャック！引き分けです");
-            print_hand("プレイヤー", &player_hands[0], false);
-            println!("結果: 引き分け - 変動なし");
+/// Whether every human seat is out of money (false at a table of bots).
+pub fn humans_broke(cfg: &GameConfig, table: &Table) -> bool {
+    table.has_humans() && table.seats.iter().filter(|s| s.controller.is_human()).all(|s| !s.player.can_play(cfg))
+}
+
+/// Deals and plays one round at the terminal and settles every seat; `None`
+/// when no round can be dealt because nobody has money to bet. `resume`
+/// continues a saved round from where it stopped instead of dealing, and
+/// `save` is given the round after the deal and after every action.
+pub fn play_round(
+    cfg: &GameConfig,
+    table: &mut Table,
+    shoe: &mut Shoe,
+    resume: Option<PlayedRound>,
+    save: &mut SaveRound,
+) -> anyhow::Result<Option<PlayedRound>> {
+    let multi = table.seats.len() > 1;
+    // a round resumed after the insurance offers picks up at the seat whose turn it was
+    let mut first_turn = None;
+    let (mut round, playing) = match resume {
+        Some(played) => {
+            print_resumed(cfg, table, &played);
+            if played.round.pending_insurance().is_none() {
+                first_turn = Some(played.round.active().map_or(played.seats.len(), |(spot, _)| spot));
+            }
+            (played.round, played.seats)
+        }
+        None => {
+            let Some((round, playing)) = deal_round(cfg, table, shoe) else {
+                return Ok(None);
+            };
+            save(&playing, &round, shoe)?;
+            (round, playing)
+        }
+    };
+    let checkpoint = &mut |round: &Round, shoe: &Shoe| save(&playing, round, shoe);
+
+    while let Some(spot) = round.pending_insurance() {
+        let seat = &table.seats[playing[spot]];
+        if seat.controller.is_human() {
+            insurance_turn(cfg, shoe, &mut round, multi.then_some(seat.name.as_str()), checkpoint)?;
         } else {
-            println!("ディーラーの勝利");
-            print_hand("プレイヤー", &player_hands[0], false);
-            println!("損失: -{}{}", cfg.bet_amount, cfg.currency_name);
+            bot_insurance(cfg, shoe, &mut round, &seat.name, seat.controller, checkpoint)?;
+        }
+    }
+
+    // Check for dealer blackjack (found by the peek; ENHC finds it after play)
+    if round.hole_revealed() && round.dealer_has_blackjack() {
+        println!("\n{}", t!("game.dealer_blackjack"));
+        print_hand(&t!("game.dealer"), &round.dealer, false);
+        for (spot, &i) in playing.iter().enumerate() {
+            let seat = &mut table.seats[i];
+            if seat.controller.is_human() {
+                let name = if multi { seat.name.clone() } else { t!("game.player") };
+                print_hand(&name, &round.spots[spot].hands[0].cards, false);
+                let outcome = round.outcomes(spot, cfg.player_edge)[0];
+                println!("{}", t!("game.result", outcome = outcome.label()));
+                finish_round(cfg, &mut seat.player, &round, spot);
+            } else {
+                seat.player.settle(round.net(spot, cfg.player_edge), cfg);
+            }
         }
-        
-        let continue_playing = show_post_game_menu(cfg)?;
-        if continue_playing {
-            return run_game(cfg);
+        if multi {
+            print_table_summary(cfg, table, &playing, &round);
         }
-        return Ok(());
+        return Ok(Some(PlayedRound { round, seats: playing }));
     }
 
-    let mut total_bet = cfg.bet_amount;
+    for (spot, &i) in playing.iter().enumerate().skip(first_turn.unwrap_or(0)) {
+        let seat = &table.seats[i];
+        if seat.controller.is_human() {
+            if multi {
+                println!("\n{}", t!("game.seat_turn", name = seat.name));
+            }
+            human_turn(cfg, shoe, &mut round, spot, multi.then_some(seat.name.as_str()), checkpoint)?;
+        } else {
+            println!();
+            bot_turn(cfg, shoe, &mut round, &seat.name, seat.controller, checkpoint)?;
+        }
+    }
 
-    // Process each player hand (initially just one, but can become multiple with splits)
-    let mut hand_index = 0;
-    while hand_index < player_hands.len() {
-        let mut current_hand = player_hands[hand_index].clone();
-        
-        println!("\n--- 手札 {} ---", hand_index + 1);
-        
-        // Check for player blackjack
-        if is_blackjack(&current_hand) {
-            println!("ブラックジャック！");
-            print_hand("プレイヤー", &current_hand, false);
-            player_hands[hand_index] = current_hand;
-            hand_index += 1; // Move to next hand
-            continue;
+    // Skip the dealer turn when no hand is left to beat
+    if !round.needs_dealer() {
+        let all_bust = round.spots.iter().flat_map(|s| &s.hands).all(|h| hand_value(&h.cards) > 21);
+        if all_bust {
+            println!("\n{}", t!("game.all_bust"));
+        } else {
+            println!("\n{}", t!("game.dealer_turn"));
         }
-        
-        // Check if this is the first action for this hand (for split/double/surrender eligibility)
-        let is_first_action = current_hand.len() == 2;
-        
-        match player_turn(&mut deck, &mut current_hand, is_first_action)? {
-            PlayerActionResult::Stand => {
-                player_hands[hand_index] = current_hand;
-                hand_index += 1; // Move to next hand
-            }
-            PlayerActionResult::DoubleDown => {
-                total_bet += cfg.bet_amount;
-                player_hands[hand_index] = current_hand;
-                println!("ダブルダウンで総ベット額: {}{}", total_bet, cfg.currency_name);
-                hand_index += 1; // Move to next hand
-            }
-            PlayerActionResult::Split(hand1, hand2) => {
-                total_bet += cfg.bet_amount;
-                player_hands[hand_index] = hand1;
-                player_hands.push(hand2);
-                println!("スプリットで総ベット額: {}{}", total_bet, cfg.currency_name);
-                // Don't increment hand_index - continue with current hand (hand1)
-            }
-            PlayerActionResult::Surrender => {
-                let refund = cfg.bet_amount / 2;
-                println!("サレンダー - 返却額: {}{}", refund, cfg.currency_name);
-                
-                // Post-game menu
-                let continue_playing = show_post_game_menu(cfg)?;
-                
-                if continue_playing {
-                    return run_game(cfg);
-                }
-                
-                return Ok(());
+        round.play_dealer(shoe);
+        print_hand(&t!("game.dealer"), &round.dealer, false);
+        println!("{}", t!("game.dealer_no_draw"));
+    } else {
+        // dealer reveals and plays
+        println!("\n{}", t!("game.dealer_turn"));
+        print_hand(&t!("game.dealer"), &round.dealer, false);
+        round.play_dealer(shoe);
+        print_hand(&t!("game.dealer_final"), &round.dealer, false);
+    }
+
+    for (spot, &i) in playing.iter().enumerate() {
+        let seat = &mut table.seats[i];
+        if seat.controller.is_human() {
+            if multi {
+                println!("\n{}", t!("game.seat_result", name = seat.name));
             }
+            report_human(cfg, &mut seat.player, &round, spot, multi.then_some(seat.name.as_str()));
+        } else {
+            seat.player.settle(round.net(spot, cfg.player_edge), cfg);
         }
     }
+    if multi {
+        print_table_summary(cfg, table, &playing, &round);
+    }
+    Ok(Some(PlayedRound { round, seats: playing }))
+}
 
-    // Check if all player hands are bust - if so, skip dealer turn
-    let all_hands_bust = player_hands.iter().all(|hand| hand_value(hand) > 21);
+/// Takes the bets and deals; `None` when nobody has money to bet. Returns the
+/// round with, for each spot, the seat that plays it.
+fn deal_round(cfg: &GameConfig, table: &Table, shoe: &mut Shoe) -> Option<(Round, Vec<usize>)> {
+    let multi = table.seats.len() > 1;
+    if humans_broke(cfg, table) {
+        for seat in table.seats.iter().filter(|s| s.controller.is_human()) {
+            println!("{}", t!("game.insufficient_bank", bank = seat.player.bank, currency = cfg.currency_name));
+        }
+        return None;
+    }
+    // seats with money to bet, in dealing order; spot n of the round is playing[n]
+    let playing: Vec<usize> = (0..table.seats.len()).filter(|&i| table.seats[i].player.can_play(cfg)).collect();
+    if playing.is_empty() {
+        println!("{}", t!("game.no_seats"));
+        return None;
+    }
 
-    if all_hands_bust {
-        println!("\n--- 全ての手札がバスト ---");
-        print_hand("ディーラー", &dealer_hand, false);
-        println!("ディーラーはカードを引く必要がありません");
-    } else {
-        // dealer reveals and plays
-        println!("\n--- ディーラーのターン ---");
-        print_hand("ディーラー", &dealer_hand, false);
-        dealer_turn(&mut deck, &mut dealer_hand);
-        print_hand("ディーラー最終", &dealer_hand, false);
+    for seat in table.seats.iter().filter(|s| s.controller.is_human()) {
+        if multi {
+            println!("{}", t!("game.seat_bank", name = seat.name, bank = seat.player.bank, currency = cfg.currency_name));
+        } else {
+            println!("{}", t!("game.bank", bank = seat.player.bank, currency = cfg.currency_name));
+        }
+        println!(
+            "{}",
+            t!("game.bet", bet = seat.player.bet, currency = cfg.currency_name, system = cfg.betting_system.label())
+        );
     }
+    if shoe.needs_shuffle() {
+        shoe.shuffle();
+        println!("{}", t!("game.shuffled", decks = cfg.rules.decks));
+    }
+    println!("{}", t!("game.shoe_remaining", cards = shoe.remaining()));
+
+    let tc = shoe.true_count();
+    let wagers: Vec<Wager> = playing
+        .iter()
+        .map(|&i| Wager { bet: table.seats[i].wager(cfg, tc), bankroll: table.seats[i].player.bank })
+        .collect();
+    for (seat, wager) in table.seats.iter().enumerate().filter(|(_, s)| !s.controller.is_human()) {
+        match playing.iter().position(|&i| i == seat) {
+            Some(spot) => println!(
+                "{}",
+                t!(
+                    "game.bot_wager",
+                    name = wager.name,
+                    kind = wager.controller.label(),
+                    bet = wagers[spot].bet,
+                    bank = wager.player.bank,
+                    currency = cfg.currency_name
+                )
+            ),
+            None => println!("{}", t!("game.bot_sits_out", name = wager.name, kind = wager.controller.label())),
+        }
+    }
+    println!();
+
+    let round = Round::deal(shoe, &wagers, &cfg.rules);
+    print_hand(&t!("game.dealer"), &round.dealer, true);
+    Some((round, playing))
+}
+
+/// Shows a saved round as it stood: the dealer's cards and every seat's
+/// hands and bets.
+fn print_resumed(cfg: &GameConfig, table: &Table, played: &PlayedRound) {
+    let round = &played.round;
+    println!("{}", t!("game.round_resumed"));
+    print_hand(&t!("game.dealer"), &round.dealer, !round.hole_revealed());
+    let multi = table.seats.len() > 1;
+    for (spot, &i) in played.seats.iter().enumerate() {
+        let seat = &table.seats[i];
+        let name = if multi { seat.name.clone() } else { t!("game.player") };
+        let hands = &round.spots[spot].hands;
+        for (index, hand) in hands.iter().enumerate() {
+            let label = if hands.len() > 1 { t!("game.named_hand", name = name, hand = index + 1) } else { name.clone() };
+            print_hand(&label, &hand.cards, false);
+        }
+        println!("{}", t!("game.resumed_bet", name = name, bet = round.total_bet(spot), currency = cfg.currency_name));
+    }
+}
 
-    let dv = hand_value(&dealer_hand);
+/// Prompts for every hand of a human seat, including hands created by splits.
+fn human_turn(
+    cfg: &GameConfig,
+    shoe: &mut Shoe,
+    round: &mut Round,
+    spot: usize,
+    seat: Option<&str>,
+    checkpoint: &mut Checkpoint,
+) -> anyhow::Result<()> {
+    // Check for player blackjack
+    if round.spots[spot].hands[0].is_blackjack() {
+        println!("\n{}", t!("game.hand_header", hand = 1));
+        println!("{}", t!("game.blackjack"));
+        print_hand(&seat.map_or_else(|| t!("game.player"), str::to_string), &round.spots[spot].hands[0].cards, false);
+    }
+
+    // Process each player hand (initially just one, but can become multiple with splits)
+    while let Some((s, hand_index)) = round.active()
+        && s == spot
+    {
+        let hands_before = round.spots[spot].hands.len();
+        println!("\n{}", t!("game.hand_header", hand = hand_index + 1));
+
+        player_turn(cfg, shoe, round, seat, checkpoint)?;
+
+        let hand = &round.spots[spot].hands[hand_index];
+        if round.spots[spot].hands.len() > hands_before {
+            println!("{}", t!("game.split_total", bet = round.total_bet(spot), currency = cfg.currency_name));
+        } else if hand.doubled {
+            println!("{}", t!("game.double_total", bet = round.total_bet(spot), currency = cfg.currency_name));
+        } else if hand.surrendered {
+            println!("{}", t!("game.surrender_refund", amount = hand.bet / 2, currency = cfg.currency_name));
+        }
+    }
+    Ok(())
+}
+
+fn report_human(cfg: &GameConfig, player: &mut PlayerState, round: &Round, spot: usize, seat: Option<&str>) {
+    let dv = hand_value(&round.dealer);
+    let name = seat.map_or_else(|| t!("game.player"), str::to_string);
 
     // Evaluate each hand
-    let mut total_winnings = 0i32;
-    
-    for (i, hand) in player_hands.iter().enumerate() {
-        let pv = hand_value(hand);
-        println!("\n--- 手札 {} の結果 ---", i + 1);
-        print_hand(&format!("プレイヤー手札{}", i + 1), hand, false);
-        
-        let player_has_bj = is_blackjack(hand);
-        let dealer_has_bj = is_blackjack(&dealer_hand);
-        
-        let result = if pv > 21 {
-            "バスト - 負け"
-        } else if dv > 21 {
-            "ディーラーがバスト - 勝ち"
-        } else if player_has_bj && !dealer_has_bj {
-            "ブラックジャック - 勝ち"
-        } else if !player_has_bj && dealer_has_bj {
-            "ディーラーブラックジャック - 負け"
-        } else if player_has_bj && dealer_has_bj {
-            "両方ブラックジャック - 引き分け"
-        } else if pv > dv {
-            "勝ち"
-        } else if pv < dv {
-            "負け"
+    let hands = &round.spots[spot].hands;
+    for (i, (hand, outcome)) in hands.iter().zip(round.outcomes(spot, cfg.player_edge)).enumerate() {
+        println!("\n{}", t!("game.hand_result_header", hand = i + 1));
+        print_hand(&t!("game.named_hand", name = name, hand = i + 1), &hand.cards, false);
+        println!(
+            "{}",
+            t!("game.versus", name = name, total = hand_value(&hand.cards), dealer = dv, outcome = outcome.label())
+        );
+
+        let amount = outcome.payout(hand.bet, cfg.rules.blackjack_payout);
+        if amount > 0 {
+            println!("{}", t!("game.won", amount = amount, currency = cfg.currency_name));
+        } else if amount < 0 {
+            println!("{}", t!("game.lost", amount = -amount, currency = cfg.currency_name));
         } else {
-            // tie broken by player_edge bias
-            if cfg.player_edge > 0.0 {
-                "勝ち (エッジ)"
-            } else {
-                "引き分け"
-            }
-        };
-
-        println!("プレイヤー: {} vs ディーラー: {} => {}", pv, dv, result);
-        
-        let hand_bet = cfg.bet_amount;
-        match result {
-            s if s.contains("勝ち") => {
-                total_winnings += hand_bet as i32;
-                println!("獲得: +{}{}", hand_bet, cfg.currency_name);
-            }
-            s if s.contains("負け") => {
-                total_winnings -= hand_bet as i32;
-                println!("損失: -{}{}", hand_bet, cfg.currency_name);
-            }
-            _ => {
-                println!("引き分け: 変動なし");
-            }
+            println!("{}", t!("game.hand_push"));
         }
     }
 
-    println!("\n--- 総合結果 ---");
-    println!("総ベット額: {}{}", total_bet, cfg.currency_name);
-    if total_winnings > 0 {
-        println!("総獲得: +{}{}", total_winnings, cfg.currency_name);
-    } else if total_winnings < 0 {
-        println!("総損失: {}{}", -total_winnings, cfg.currency_name);
+    println!("\n{}", t!("game.summary_header"));
+    println!("{}", t!("game.total_bet", bet = round.total_bet(spot), currency = cfg.currency_name));
+    finish_round(cfg, player, round, spot);
+}
+
+/// Every seat's result against the one dealer hand.
+fn print_table_summary(cfg: &GameConfig, table: &Table, playing: &[usize], round: &Round) {
+    let dv = hand_value(&round.dealer);
+    let dealer = if round.dealer_has_blackjack() {
+        t!("game.blackjack_short")
+    } else if dv > 21 {
+        t!("game.bust_total", total = dv)
     } else {
-        println!("総合結果: 引き分け");
+        dv.to_string()
+    };
+    println!("\n{}", t!("game.table_result", dealer = dealer));
+    for (i, seat) in table.seats.iter().enumerate() {
+        let bank = seat.player.bank;
+        match playing.iter().position(|&p| p == i) {
+            Some(spot) => {
+                let outcomes: Vec<String> = round.outcomes(spot, cfg.player_edge).iter().map(|o| o.label()).collect();
+                println!(
+                    "{}",
+                    t!(
+                        "game.table_seat",
+                        name = seat.name,
+                        outcomes = outcomes.join(", "),
+                        net = format!("{:+}", round.net(spot, cfg.player_edge)),
+                        bank = bank,
+                        currency = cfg.currency_name
+                    )
+                );
+            }
+            None => println!("{}", t!("game.table_sat_out", name = seat.name, bank = bank, currency = cfg.currency_name)),
+        }
     }
-    
-    // Post-game menu
-    let continue_playing = show_post_game_menu(cfg)?;
-    
-    if continue_playing {
-        return run_game(cfg);
+}
+
+fn finish_round(cfg: &GameConfig, player: &mut PlayerState, round: &Round, spot: usize) {
+    if round.spots[spot].insurance > 0 {
+        let insurance = round.insurance_net(spot);
+        if insurance > 0 {
+            println!("{}", t!("game.insurance_won", amount = insurance, currency = cfg.currency_name));
+        } else {
+            println!("{}", t!("game.insurance_lost", amount = -insurance, currency = cfg.currency_name));
+        }
     }
-    
-    Ok(())
+    let net = round.net(spot, cfg.player_edge);
+    if net > 0 {
+        println!("{}", t!("game.net_won", amount = net, currency = cfg.currency_name));
+    } else if net < 0 {
+        println!("{}", t!("game.net_lost", amount = -net, currency = cfg.currency_name));
+    } else {
+        println!("{}", t!("game.net_push"));
+    }
+    player.settle(net, cfg);
+    println!("{}", t!("game.bank", bank = player.bank, currency = cfg.currency_name));
 }
 
-fn show_post_game_menu(cfg: &GameConfig) -> anyhow::Result<bool> {
+/// The menu between rounds; `false` when the player leaves the table.
+pub fn post_game_menu(cfg: &mut GameConfig, table: &mut Table, shoe: &mut Shoe) -> anyhow::Result<bool> {
     loop {
         println!();
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("次のアクションを選択してください:")
-            .default(0)
-            .items(PostGameChoice::menu_items())
-            .interact()?;
+        let selection = prompt::select(&t!("menu.post_game_prompt"), &PostGameChoice::menu_items(), 0)?;
 
         match PostGameChoice::from_index(selection) {
             Some(PostGameChoice::NextRound) => {
-                println!("新しいラウンドを開始します...");
+                println!("{}", t!("menu.next_round_start"));
                 return Ok(true); // Continue playing
             }
             Some(PostGameChoice::ChangeBet) => {
-                println!("賭け金変更機能は今後実装予定です");
-                println!("現在のベット額: {}{}", cfg.bet_amount, cfg.currency_name);
+                let Some(seat) = pick_human_seat(table)? else {
+                    continue;
+                };
+                let player = &mut table.seats[seat].player;
+                match pick_bet(cfg, player, shoe.true_count())? {
+                    Some(BetChoice::Unit(amount)) => player.set_unit(amount, cfg),
+                    Some(BetChoice::NextRound(amount)) => player.bet = amount,
+                    None => {}
+                }
+                println!("{}", t!("menu.next_bet", bet = player.bet, currency = cfg.currency_name));
             }
             Some(PostGameChoice::Settings) => {
-                println!("設定機能は今後実装予定です");
-                println!("設定ファイル: game_config.toml で設定を変更できます");
+                let before = cfg.clone();
+                edit_settings(cfg)?;
+                apply_settings(&before, cfg, table, shoe);
             }
             Some(PostGameChoice::Quit) => {
-                println!("メインメニューに戻ります");
+                println!("{}", t!("menu.back_to_main"));
                 return Ok(false); // Stop playing
             }
             None => {
-                println!("無効な選択です");
+                println!("{}", t!("menu.invalid"));
             }
         }
     }
 }
 
+/// Carries settings changed between rounds over to the table and the shoe.
+fn apply_settings(before: &GameConfig, cfg: &GameConfig, table: &mut Table, shoe: &mut Shoe) {
+    if before.rules.decks != cfg.rules.decks || before.rules.penetration != cfg.rules.penetration {
+        *shoe = Shoe::new(cfg.rules.decks, cfg.rules.penetration, rand::random());
+    }
+    if before.bet_amount != cfg.bet_amount {
+        for seat in table.seats.iter_mut().filter(|s| s.controller.is_human()) {
+            seat.player.set_unit(cfg.bet_amount, cfg);
+        }
+    }
+}
+
+/// Asks which human seat to act for; skips the question when there is only one.
+fn pick_human_seat(table: &Table) -> anyhow::Result<Option<usize>> {
+    let humans: Vec<usize> = (0..table.seats.len()).filter(|&i| table.seats[i].controller.is_human()).collect();
+    match humans.as_slice() {
+        [] => {
+            println!("{}", t!("menu.no_humans"));
+            Ok(None)
+        }
+        [only] => Ok(Some(*only)),
+        _ => {
+            let mut items: Vec<String> = humans.iter().map(|&i| table.seats[i].name.clone()).collect();
+            items.push(t!("menu.back"));
+            let selection = prompt::select(&t!("menu.pick_seat"), &items, 0)?;
+            Ok(humans.get(selection).copied())
+        }
+    }
+}
+
 pub fn run_menu_loop(cfg: &GameConfig) -> anyhow::Result<()> {
-    println!("ブラックジャックへようこそ！");
-    println!("プレイヤー資金: {}{} (通貨名: {})", 
-             cfg.player_starting_bank, cfg.currency_name, cfg.currency_full_name);
-    println!("矢印キーで選択、Enterで決定、または 'cargo run -- --help' でCLIオプションを確認\n");
-    
+    println!("{}", t!("menu.welcome"));
+    let (mut session, resumed) = session::open(cfg, rand::random())?;
+    let session = &mut session;
+    session.exit_on_interrupt()?;
+    if !resumed {
+        println!(
+            "{}",
+            t!("menu.starting_bank", bank = cfg.player_starting_bank, currency = cfg.currency_name, full_name = cfg.currency_full_name)
+        );
+    }
+    let table = &session.table;
+    if table.seats.len() > 1 {
+        let seats: Vec<String> =
+            table.seats.iter().map(|s| t!("menu.seat_entry", name = s.name, kind = s.controller.label())).collect();
+        println!("{}", t!("menu.seats", seats = seats.join(", ")));
+    }
+    print_rules(&session.cfg);
+    if resumed {
+        play_session(session);
+    } else {
+        println!("{}\n", t!("menu.intro"));
+    }
+
     loop {
         match get_user_choice() {
-            Ok(MenuChoice::StartGame) => {
-                match run_game(cfg) {
-                    Ok(()) => {
-                        // ゲームが正常終了（メインメニューに戻る選択）
-                    }
-                    Err(e) => {
-                        if e.to_string().contains("interrupted") {
-                            println!("\nゲームが中断されました");
-                        } else {
-                            eprintln!("ゲームエラー: {}", e);
-                        }
+            Ok(MenuChoice::StartGame) => play_session(session),
+            Ok(MenuChoice::Trainer) => {
+                if let Err(e) = run_trainer(&session.cfg) {
+                    if prompt::is_interrupted(&e) {
+                        println!("\n{}", t!("menu.trainer_interrupted"));
+                    } else {
+                        eprintln!("{}", t!("menu.trainer_error", error = e));
                     }
                 }
             }
+            // a fresh shoe would let an unfinished round be dealt new cards
+            Ok(MenuChoice::Rules) if session.in_round() => println!("{}", t!("session.finish_round_first")),
+            // the rules can be switched between sessions of play
+            Ok(MenuChoice::Rules) => match pick_preset(&session.cfg) {
+                Ok(Some(preset)) => {
+                    let cfg = &mut session.cfg;
+                    cfg.set_preset(preset);
+                    session.shoe = Shoe::new(cfg.rules.decks, cfg.rules.penetration, rand::random());
+                    println!("{}", t!("rules.selected", name = preset.label()));
+                    print_rules(cfg);
+                    if let Err(e) = session.save() {
+                        eprintln!("{}", t!("session.save_failed", error = e));
+                    }
+                }
+                Ok(None) => {}
+                Err(e) if prompt::is_interrupted(&e) => {}
+                Err(e) => eprintln!("{}", t!("menu.menu_error", error = e)),
+            },
             Ok(MenuChoice::ShowHelp) => {
                 display_help();
             }
             Ok(MenuChoice::Quit) => {
-                println!("ご利用ありがとうございました！");
+                println!("{}", t!("menu.thanks"));
                 break;
             }
             Err(e) => {
                 // Handle Ctrl+C or other interruptions gracefully
-                if e.to_string().contains("interrupted") {
-                    println!("\nさようなら！");
+                if prompt::is_interrupted(&e) {
+                    println!("\n{}", t!("menu.goodbye"));
                     break;
                 } else {
-                    eprintln!("メニューエラー: {}", e);
+                    eprintln!("{}", t!("menu.menu_error", error = e));
                     break;
                 }
             }
         }
     }
-    
+
+    // kept for "resume" on the next launch
+    if let Err(e) = session.save() {
+        eprintln!("{}", t!("session.save_failed", error = e));
+    }
     Ok(())
 }
+
+/// Plays the session until the player goes back to the menu.
+fn play_session(session: &mut Session) {
+    match session.run() {
+        Ok(()) => {}
+        // the session has said it was saved
+        Err(e) if prompt::is_interrupted(&e) => {}
+        Err(e) => eprintln!("{}", t!("menu.game_error", error = e)),
+    }
+}
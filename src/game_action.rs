@@ -1,53 +1,58 @@
 use crate::card::Card;
+use crate::t;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GameAction {
     Hit,
     Stand,
+    #[serde(alias = "double")]
     DoubleDown,
     Split,
     Surrender,
 }
 
-#[derive(Debug, Clone)]
-pub enum PlayerActionResult {
-    Stand,
-    DoubleDown,
-    Split(Vec<Card>, Vec<Card>), // Two split hands
-    Surrender,
-}
-
 impl GameAction {
-    pub fn menu_items(can_double: bool, can_split: bool, can_surrender: bool) -> Vec<&'static str> {
-        let mut items = vec!["ヒット（カードを引く）", "スタンド（現在の手札で勝負）"];
-        
-        if can_double {
-            items.push("ダブルダウン（ベット2倍、1枚のみ引く）");
+    /// Catalog key; the same name the protocols serialize.
+    pub fn key(&self) -> &'static str {
+        match self {
+            GameAction::Hit => "hit",
+            GameAction::Stand => "stand",
+            GameAction::DoubleDown => "double_down",
+            GameAction::Split => "split",
+            GameAction::Surrender => "surrender",
         }
-        if can_split {
-            items.push("スプリット（手札を分割）");
-        }
-        if can_surrender {
-            items.push("サレンダー（降参、半額返却）");
-        }
-        
-        items
     }
 
-    pub fn get_valid_actions(can_double: bool, can_split: bool, can_surrender: bool) -> Vec<Self> {
-        let mut actions = vec![GameAction::Hit, GameAction::Stand];
-        
-        if can_double {
-            actions.push(GameAction::DoubleDown);
-        }
-        if can_split {
-            actions.push(GameAction::Split);
-        }
-        if can_surrender {
-            actions.push(GameAction::Surrender);
+    pub fn label(&self) -> String {
+        t!(&format!("action_label.{}", self.key()))
+    }
+
+    /// Action name without the explanation, for announcing other seats' plays.
+    pub fn name(&self) -> String {
+        t!(&format!("action.{}", self.key()))
+    }
+
+    /// Single-key shortcut (`h`/`s`/`d`/`p`/`r`) for the keyboard input mode and the TUI.
+    pub fn shortcut(&self) -> char {
+        match self {
+            GameAction::Hit => 'h',
+            GameAction::Stand => 's',
+            GameAction::DoubleDown => 'd',
+            GameAction::Split => 'p',
+            GameAction::Surrender => 'r',
         }
-        
-        actions
+    }
+
+    pub fn from_shortcut(key: char) -> Option<Self> {
+        [GameAction::Hit, GameAction::Stand, GameAction::DoubleDown, GameAction::Split, GameAction::Surrender]
+            .into_iter()
+            .find(|a| a.shortcut() == key.to_ascii_lowercase())
+    }
+
+    pub fn menu_items(actions: &[Self]) -> Vec<String> {
+        actions.iter().map(|a| a.label()).collect()
     }
 }
 
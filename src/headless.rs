@@ -0,0 +1,215 @@
+use crate::blackjack::HandOutcome;
+use crate::card::{Card, hand_value};
+use crate::config::GameConfig;
+use crate::game_action::GameAction;
+use crate::round::{Round, Wager};
+use crate::shoe::Shoe;
+use crate::strategy::is_soft;
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+/// What the session is waiting for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// waiting for a bet to deal the next round
+    Betting,
+    Insurance,
+    PlayerTurn,
+    /// the bankroll is below the table minimum
+    GameOver,
+}
+
+/// One settled round in the session history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundRecord {
+    pub round: u32,
+    pub bet: u32,
+    pub outcomes: Vec<HandOutcome>,
+    pub net: i64,
+    pub bank: u32,
+}
+
+/// A single-seat game driven by calls instead of a terminal, for the REST
+/// API and other programmatic front ends.
+#[derive(Serialize, Deserialize)]
+pub struct HeadlessSession {
+    pub config: GameConfig,
+    pub bank: u32,
+    shoe: Shoe,
+    /// the round in play, or the last one once settled
+    round: Option<Round>,
+    settled: bool,
+    pub history: Vec<RoundRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HandState {
+    pub cards: Vec<Card>,
+    pub total: u8,
+    pub soft: bool,
+    pub bet: u32,
+    pub doubled: bool,
+    pub surrendered: bool,
+    /// set once the round is settled
+    pub outcome: Option<HandOutcome>,
+}
+
+/// Everything a client may see; the hole card is `None` until revealed.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionState {
+    pub phase: Phase,
+    pub bank: u32,
+    pub currency: String,
+    pub min_bet: u32,
+    pub max_bet: u32,
+    pub dealer: Vec<Option<Card>>,
+    /// total of the dealer's visible cards
+    pub dealer_total: u8,
+    pub hands: Vec<HandState>,
+    pub active_hand: Option<usize>,
+    pub allowed_actions: Vec<GameAction>,
+    /// price of insurance while it is offered
+    pub insurance_cost: Option<u32>,
+    pub last_result: Option<RoundRecord>,
+}
+
+impl HeadlessSession {
+    pub fn new(config: GameConfig, seed: u64) -> Self {
+        let shoe = Shoe::new(config.rules.decks, config.rules.penetration, seed);
+        let bank = config.player_starting_bank;
+        Self { config, bank, shoe, round: None, settled: false, history: Vec::new() }
+    }
+
+    pub fn phase(&self) -> Phase {
+        match &self.round {
+            Some(round) if !self.settled && round.pending_insurance().is_some() => Phase::Insurance,
+            Some(_) if !self.settled => Phase::PlayerTurn,
+            _ if self.bank == 0 || self.bank < self.config.table_min_bet => Phase::GameOver,
+            _ => Phase::Betting,
+        }
+    }
+
+    pub fn shoe_remaining(&self) -> usize {
+        self.shoe.remaining()
+    }
+
+    fn max_bet(&self) -> u32 {
+        self.config.table_max_bet.min(self.bank)
+    }
+
+    /// Places the bet and deals the next round.
+    pub fn bet(&mut self, amount: u32) -> anyhow::Result<()> {
+        if self.phase() != Phase::Betting {
+            anyhow::bail!(t!("error.bet_phase", phase = format!("{:?}", self.phase())));
+        }
+        if amount < self.config.table_min_bet || amount > self.max_bet() {
+            anyhow::bail!(t!(
+                "error.bet_range",
+                min = self.config.table_min_bet,
+                max = self.max_bet(),
+                currency = self.config.currency_name
+            ));
+        }
+        if self.shoe.needs_shuffle() {
+            self.shoe.shuffle();
+        }
+        self.round = Some(Round::deal(&mut self.shoe, &[Wager { bet: amount, bankroll: self.bank }], &self.config.rules));
+        self.settled = false;
+        self.settle_if_done();
+        Ok(())
+    }
+
+    pub fn insurance(&mut self, take: bool) -> anyhow::Result<()> {
+        if self.phase() != Phase::Insurance {
+            anyhow::bail!(t!("error.no_insurance"));
+        }
+        if let Some(round) = &mut self.round {
+            round.resolve_insurance(&mut self.shoe, take);
+        }
+        self.settle_if_done();
+        Ok(())
+    }
+
+    pub fn act(&mut self, action: GameAction) -> anyhow::Result<()> {
+        if self.phase() != Phase::PlayerTurn {
+            anyhow::bail!(t!("error.action_phase", phase = format!("{:?}", self.phase())));
+        }
+        if let Some(round) = &mut self.round {
+            round.apply(&mut self.shoe, action)?;
+        }
+        self.settle_if_done();
+        Ok(())
+    }
+
+    /// Plays the dealer and books the result once no decision is left.
+    fn settle_if_done(&mut self) {
+        let Some(round) = &mut self.round else {
+            return;
+        };
+        if self.settled || round.pending_insurance().is_some() || round.active().is_some() {
+            return;
+        }
+        round.play_dealer(&mut self.shoe);
+        let net = round.net(0, self.config.player_edge);
+        self.bank = (i64::from(self.bank) + net).max(0) as u32;
+        self.history.push(RoundRecord {
+            round: self.history.len() as u32 + 1,
+            bet: round.spots[0].hands[0].bet,
+            outcomes: round.outcomes(0, self.config.player_edge),
+            net,
+            bank: self.bank,
+        });
+        self.settled = true;
+    }
+
+    pub fn state(&self) -> SessionState {
+        let phase = self.phase();
+        let mut state = SessionState {
+            phase,
+            bank: self.bank,
+            currency: self.config.currency_name.clone(),
+            min_bet: self.config.table_min_bet,
+            max_bet: self.max_bet(),
+            dealer: Vec::new(),
+            dealer_total: 0,
+            hands: Vec::new(),
+            active_hand: None,
+            allowed_actions: Vec::new(),
+            insurance_cost: None,
+            last_result: self.history.last().filter(|_| self.settled).cloned(),
+        };
+        let Some(round) = &self.round else {
+            return state;
+        };
+
+        let hidden = !round.hole_revealed();
+        state.dealer = round.dealer.iter().enumerate().map(|(i, c)| (i > 0 || !hidden).then_some(*c)).collect();
+        let visible: Vec<Card> = state.dealer.iter().flatten().copied().collect();
+        state.dealer_total = hand_value(&visible);
+
+        let outcomes = self.settled.then(|| round.outcomes(0, self.config.player_edge));
+        state.hands = round.spots[0]
+            .hands
+            .iter()
+            .enumerate()
+            .map(|(i, h)| HandState {
+                cards: h.cards.clone(),
+                total: hand_value(&h.cards),
+                soft: is_soft(&h.cards),
+                bet: h.bet,
+                doubled: h.doubled,
+                surrendered: h.surrendered,
+                outcome: outcomes.as_ref().map(|o| o[i]),
+            })
+            .collect();
+        if phase == Phase::PlayerTurn {
+            state.active_hand = round.active().map(|(_, hand)| hand);
+            state.allowed_actions = round.legal_actions();
+        }
+        if phase == Phase::Insurance {
+            state.insurance_cost = Some(round.spots[0].hands[0].bet / 2);
+        }
+        state
+    }
+}
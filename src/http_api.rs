@@ -0,0 +1,219 @@
+use crate::config::GameConfig;
+use crate::config_check::{Severity, check_values};
+use crate::game_action::GameAction;
+use crate::headless::HeadlessSession;
+use crate::session_store::{FileStore, MemoryStore, SessionStore};
+use crate::t;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+pub struct HttpOptions {
+    pub addr: String,
+    /// keep sessions as JSON files here instead of in memory
+    pub store_dir: Option<PathBuf>,
+}
+
+/// Error answered with an HTTP status and `{"error": ...}`, plus
+/// `"problems"` when a config override fails its checks.
+struct ApiError {
+    status: u16,
+    message: String,
+    problems: Vec<String>,
+}
+
+impl ApiError {
+    fn new(status: u16, message: impl Into<String>) -> Self {
+        Self { status, message: message.into(), problems: Vec::new() }
+    }
+
+    fn body(&self) -> Value {
+        if self.problems.is_empty() {
+            json!({ "error": self.message })
+        } else {
+            json!({ "error": self.message, "problems": self.problems })
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(500, e.to_string())
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct CreateBody {
+    /// overrides on top of the server's game_config.toml
+    config: Option<Value>,
+    seed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct BetBody {
+    amount: u32,
+}
+
+#[derive(Deserialize)]
+struct InsuranceBody {
+    take: bool,
+}
+
+#[derive(Deserialize)]
+struct ActionBody {
+    action: GameAction,
+}
+
+/// REST API over HTTP/JSON for playing without a terminal (see README).
+pub fn run_http(defaults: &GameConfig, opts: &HttpOptions) -> anyhow::Result<()> {
+    let server = Server::http(&opts.addr).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut store: Box<dyn SessionStore> = match &opts.store_dir {
+        Some(dir) => Box::new(FileStore::new(dir.clone())?),
+        None => Box::new(MemoryStore::default()),
+    };
+    match &opts.store_dir {
+        Some(dir) => println!("{}", t!("http.listening_files", addr = opts.addr, dir = dir.display())),
+        None => println!("{}", t!("http.listening_memory", addr = opts.addr)),
+    }
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match handle(&mut request, defaults, store.as_mut()) {
+            Ok((status, body)) => (status, body),
+            Err(e) => (e.status, e.body()),
+        };
+        let header = Header::from_bytes("Content-Type", "application/json; charset=utf-8").unwrap();
+        let response = Response::from_string(body.to_string()).with_status_code(status).with_header(header);
+        if let Err(e) = request.respond(response) {
+            eprintln!("{}", t!("http.respond_failed", error = e));
+        }
+    }
+    Ok(())
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|e| ApiError::new(400, e.to_string()))?;
+    if body.trim().is_empty() {
+        body = "{}".to_string();
+    }
+    serde_json::from_str(&body).map_err(|e| ApiError::new(400, t!("http.bad_request", error = e)))
+}
+
+fn handle(request: &mut Request, defaults: &GameConfig, store: &mut dyn SessionStore) -> Result<(u16, Value), ApiError> {
+    let url = request.url().split('?').next().unwrap_or("").to_string();
+    let path: Vec<&str> = url.trim_matches('/').split('/').collect();
+    let method = request.method().clone();
+
+    match (&method, path.as_slice()) {
+        (Method::Post, ["sessions"]) => {
+            let body: CreateBody = read_json(request)?;
+            let config = session_config(defaults, body.config)?;
+            let session = HeadlessSession::new(config, body.seed.unwrap_or_else(rand::random));
+            let id = format!("{:016x}", rand::random::<u64>());
+            let state = session.state();
+            store.put(&id, session)?;
+            Ok((201, json!({ "id": id, "state": state })))
+        }
+        (Method::Delete, ["sessions", id]) => {
+            if store.remove(checked_id(id)?)? {
+                Ok((200, json!({ "deleted": id })))
+            } else {
+                Err(not_found(id))
+            }
+        }
+        (Method::Get, ["sessions", id]) => with_session(store, id, |s| Ok(json!(s.state()))),
+        (Method::Get, ["sessions", id, "balance"]) => with_session(store, id, |s| {
+            let start = s.config.player_starting_bank;
+            Ok(json!({
+                "bank": s.bank,
+                "starting_bank": start,
+                "net": i64::from(s.bank) - i64::from(start),
+                "currency": s.config.currency_name,
+            }))
+        }),
+        (Method::Get, ["sessions", id, "history"]) => with_session(store, id, |s| Ok(json!({ "rounds": s.history }))),
+        (Method::Post, ["sessions", id, "bet"]) => {
+            let body: BetBody = read_json(request)?;
+            with_session(store, id, |s| {
+                s.bet(body.amount)?;
+                Ok(json!(s.state()))
+            })
+        }
+        (Method::Post, ["sessions", id, "insurance"]) => {
+            let body: InsuranceBody = read_json(request)?;
+            with_session(store, id, |s| {
+                s.insurance(body.take)?;
+                Ok(json!(s.state()))
+            })
+        }
+        (Method::Post, ["sessions", id, "action"]) => {
+            let body: ActionBody = read_json(request)?;
+            with_session(store, id, |s| {
+                s.act(body.action)?;
+                Ok(json!(s.state()))
+            })
+        }
+        _ => Err(ApiError::new(404, t!("http.no_route", method = method, url = url))),
+    }
+}
+
+/// Runs one request against a stored session; game rule errors answer 409.
+fn with_session(
+    store: &mut dyn SessionStore,
+    id: &str,
+    f: impl FnOnce(&mut HeadlessSession) -> anyhow::Result<Value>,
+) -> Result<(u16, Value), ApiError> {
+    let id = checked_id(id)?;
+    let mut session = store.take(id)?.ok_or_else(|| not_found(id))?;
+    let result = f(&mut session);
+    store.put(id, session)?;
+    result.map(|body| (200, body)).map_err(|e| ApiError::new(409, e.to_string()))
+}
+
+/// Ids are generated hex strings; anything else never names a session file.
+fn checked_id(id: &str) -> Result<&str, ApiError> {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(id)
+    } else {
+        Err(not_found(id))
+    }
+}
+
+fn not_found(id: &str) -> ApiError {
+    ApiError::new(404, t!("http.no_session", id = id))
+}
+
+/// The server's config with the request's overrides merged in.
+fn session_config(defaults: &GameConfig, overrides: Option<Value>) -> Result<GameConfig, ApiError> {
+    let mut merged = serde_json::to_value(defaults).map_err(anyhow::Error::from)?;
+    if let Some(overrides) = overrides {
+        merge(&mut merged, overrides);
+    }
+    let mut config: GameConfig =
+        serde_json::from_value(merged).map_err(|e| ApiError::new(400, t!("http.bad_config", error = e)))?;
+    config.deviations = defaults.deviations.clone();
+    config.apply_rules();
+    let problems: Vec<String> =
+        check_values(&config).iter().filter(|p| p.severity == Severity::Error).map(|p| p.render()).collect();
+    if !problems.is_empty() {
+        let message = t!("http.invalid_config", count = problems.len());
+        return Err(ApiError { problems, ..ApiError::new(400, message) });
+    }
+    Ok(config)
+}
+
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
@@ -0,0 +1,129 @@
+//! Message catalogs for the terminal UI. Japanese and English are built in;
+//! other languages are read from `lang/<code>.toml` (same keys as
+//! `lang/ja.toml`). Missing keys fall back to Japanese, then to the key.
+
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, RwLock};
+
+const JA: &str = include_str!("../lang/ja.toml");
+const EN: &str = include_str!("../lang/en.toml");
+
+/// Directory searched for catalogs that are not built in.
+pub const LANG_DIR: &str = "lang";
+
+/// Flattened `section.key -> text` table.
+#[derive(Debug, Clone, Default)]
+pub struct Catalog {
+    pub code: String,
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(code: &str, source: &str) -> anyhow::Result<Self> {
+        let table: toml::Table = toml::from_str(source)?;
+        let mut messages = HashMap::new();
+        flatten("", &table, &mut messages);
+        Ok(Self { code: code.to_string(), messages })
+    }
+
+    /// `ja` and `en` are built in; anything else is `lang/<code>.toml`, or a
+    /// path to a catalog file when `code` ends in `.toml`.
+    pub fn load(code: &str) -> anyhow::Result<Self> {
+        match code {
+            "ja" => Self::parse("ja", JA),
+            "en" => Self::parse("en", EN),
+            _ => {
+                let path = if code.ends_with(".toml") {
+                    Path::new(code).to_path_buf()
+                } else {
+                    Path::new(LANG_DIR).join(format!("{}.toml", code))
+                };
+                let source = fs::read_to_string(&path)
+                    .with_context(|| crate::t!("i18n.unreadable", path = path.display()))?;
+                let name = path.file_stem().map_or(code.to_string(), |s| s.to_string_lossy().into_owned());
+                Self::parse(&name, &source).with_context(|| crate::t!("i18n.malformed", path = path.display()))
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+}
+
+fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match value {
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            toml::Value::Table(inner) => flatten(&key, inner, out),
+            _ => {}
+        }
+    }
+}
+
+static FALLBACK: LazyLock<Catalog> = LazyLock::new(|| Catalog::parse("ja", JA).unwrap_or_default());
+static ACTIVE: LazyLock<RwLock<Option<Catalog>>> = LazyLock::new(|| RwLock::new(None));
+static SPOKEN: AtomicBool = AtomicBool::new(false);
+
+/// Accessible wording (`--accessible`): `spoken.<key>` entries, written as
+/// full sentences without decoration, take precedence over `<key>`.
+pub fn set_spoken(on: bool) {
+    SPOKEN.store(on, Ordering::Relaxed);
+}
+
+pub fn spoken() -> bool {
+    SPOKEN.load(Ordering::Relaxed)
+}
+
+/// Switches every later message to `code` (see `Catalog::load`).
+pub fn set_language(code: &str) -> anyhow::Result<()> {
+    let catalog = Catalog::load(code)?;
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = (catalog.code != "ja").then_some(catalog);
+    }
+    Ok(())
+}
+
+/// Code of the language in use.
+pub fn language() -> String {
+    match ACTIVE.read() {
+        Ok(active) => active.as_ref().map_or("ja".to_string(), |c| c.code.clone()),
+        Err(_) => "ja".to_string(),
+    }
+}
+
+/// Looks up `key` and fills each `{name}` with its argument; use `t!`.
+pub fn text(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let active = ACTIVE.read().ok();
+    let active = active.as_ref().and_then(|a| a.as_ref());
+    let spoken_key = spoken().then(|| format!("spoken.{}", key));
+    let template = active
+        .and_then(|c| spoken_key.as_deref().and_then(|k| c.get(k)).or_else(|| c.get(key)))
+        .or_else(|| spoken_key.as_deref().and_then(|k| FALLBACK.get(k)))
+        .or_else(|| FALLBACK.get(key))
+        .unwrap_or(key);
+    let mut out = template.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    out
+}
+
+/// `t!("menu.start")` or `t!("game.bank", amount = bank, currency = name)`.
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::text($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::text($key, &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+])
+    };
+}
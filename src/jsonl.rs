@@ -0,0 +1,72 @@
+use crate::config::GameConfig;
+use crate::env::{BlackjackEnv, Observation, Step};
+use crate::game_action::GameAction;
+use crate::headless::{HeadlessSession, Phase, SessionState};
+use crate::protocol::send;
+use crate::t;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead};
+
+/// One line read from stdin in `--protocol jsonl` mode.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Command {
+    Bet { amount: u32 },
+    Insurance { take: bool },
+    Action { action: GameAction },
+    /// repeat the current state without changing anything
+    State,
+    /// start an environment episode (see `env.rs`), with a fresh shoe if seeded
+    Reset {
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+    Step { action: GameAction },
+    Observation,
+    Quit,
+}
+
+/// One line written to stdout.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Reply {
+    State(SessionState),
+    Step(Step),
+    Observation(Observation),
+    Error { message: String },
+}
+
+/// Plays a single seat over stdin/stdout instead of the terminal prompts:
+/// every command is answered by the new state (or an error) on one line.
+/// `reset` / `step` drive a separate `BlackjackEnv` for training agents.
+pub fn run_jsonl(cfg: &GameConfig, seed: u64) -> anyhow::Result<()> {
+    let mut session = HeadlessSession::new(cfg.clone(), seed);
+    let mut env: Option<BlackjackEnv> = None;
+    let mut out = io::stdout().lock();
+    send(&mut out, &Reply::State(session.state()))?;
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let env = env.get_or_insert_with(|| BlackjackEnv::new(cfg, seed));
+        let result = match serde_json::from_str::<Command>(&line) {
+            Ok(Command::Bet { amount }) => session.bet(amount).map(|()| Reply::State(session.state())),
+            Ok(Command::Insurance { take }) => session.insurance(take).map(|()| Reply::State(session.state())),
+            Ok(Command::Action { action }) => session.act(action).map(|()| Reply::State(session.state())),
+            Ok(Command::State) => Ok(Reply::State(session.state())),
+            Ok(Command::Reset { seed }) => Ok(Reply::Step(env.reset(seed))),
+            Ok(Command::Step { action }) => env.step(action).map(Reply::Step),
+            Ok(Command::Observation) => Ok(Reply::Observation(env.observation())),
+            Ok(Command::Quit) => break,
+            Err(e) => Err(anyhow::anyhow!(t!("error.bad_message", error = e))),
+        };
+        let reply = result.unwrap_or_else(|e| Reply::Error { message: e.to_string() });
+        send(&mut out, &reply)?;
+        if session.phase() == Phase::GameOver {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Blackjack rules engine and front ends; `main.rs` is the command-line game.
+
+pub mod config;
+pub mod config_check;
+pub mod config_edit;
+pub mod config_layers;
+pub mod i18n;
+#[cfg(feature = "cli")]
+pub mod game;
+pub mod card;
+pub mod card_art;
+#[cfg(feature = "cli")]
+pub mod menu;
+#[cfg(feature = "cli")]
+pub mod prompt;
+#[cfg(feature = "cli")]
+pub mod settings;
+#[cfg(feature = "cli")]
+pub mod session;
+pub mod game_action;
+pub mod blackjack;
+pub mod round;
+pub mod rules;
+pub mod strategy;
+pub mod betting;
+pub mod player;
+pub mod simulation;
+pub mod shoe;
+pub mod advisor;
+pub mod deviations;
+#[cfg(feature = "cli")]
+pub mod trainer;
+pub mod bankroll;
+pub mod bots;
+pub mod table;
+pub mod protocol;
+pub mod server;
+#[cfg(feature = "cli")]
+pub mod client;
+pub mod events;
+pub mod headless;
+pub mod jsonl;
+pub mod env;
+pub mod ffi;
+#[cfg(feature = "http")]
+pub mod session_store;
+#[cfg(feature = "http")]
+pub mod http_api;
+#[cfg(feature = "spectator")]
+pub mod spectator;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "tui")]
+pub mod tui;
@@ -1,37 +1,285 @@
-mod config;
-mod game;
-mod card;
-mod menu;
-mod game_action;
-mod blackjack;
+use nanai_veintiuna_rs::{advisor, bankroll, betting, card, client, config, config_check, config_layers, events, game, i18n, jsonl, menu, prompt, rules, server, session, simulation, t};
+#[cfg(feature = "http")]
+use nanai_veintiuna_rs::http_api;
+#[cfg(feature = "spectator")]
+use nanai_veintiuna_rs::spectator;
+#[cfg(feature = "tui")]
+use nanai_veintiuna_rs::tui;
 
-use clap::{Arg, Command};
-use config::GameConfig;
-use std::path::Path;
+use advisor::AdvisorMode;
+use bankroll::BankrollQuery;
+use betting::BettingSystem;
+use clap::{Arg, ArgMatches, Command, builder::PossibleValuesParser, parser::ValueSource, value_parser};
+use config::{CardStyle, GameConfig};
+use config_layers::{Entry, Input};
+use rules::Preset;
+use simulation::SimOptions;
+use server::ServeOptions;
+use std::path::PathBuf;
+use std::time::Duration;
 
 fn main() -> anyhow::Result<()> {
-    let matches = Command::new("blackjack") // "nanai_veintiuna_rs" | "blackjack" | "veintiuna"
+    // the help text is written when the command is built, so --lang goes first
+    if let Some(lang) = early_lang() {
+        i18n::set_language(&lang)?;
+    }
+    let cli = Command::new("blackjack") // "nanai_veintiuna_rs" | "blackjack" | "veintiuna"
         .version("0.1.0")
-        .about("メニュー駆動式ブラックジャックゲーム")
+        .about(t!("cli.about"))
         .arg(Arg::new("config")
             .short('c')
             .long("config")
-            .value_name("ファイル")
-            .help("カスタム設定ファイルを指定")
+            .value_name(t!("cli.file_value"))
+            .help(t!("cli.config"))
             .default_value("game_config.toml"))
         .arg(Arg::new("direct")
             .short('d')
             .long("direct")
             .action(clap::ArgAction::SetTrue)
-            .help("メニューをスキップして直接ゲーム開始"))
-        .get_matches();
+            .help(t!("cli.direct")))
+        .arg(Arg::new("players")
+            .short('p')
+            .long("players")
+            .value_name(t!("cli.players_value"))
+            .value_delimiter(',')
+            .help(t!("cli.players")))
+        .arg(Arg::new("protocol")
+            .long("protocol")
+            .value_parser(["tty", "jsonl"])
+            .default_value("tty")
+            .help(t!("cli.protocol")))
+        .arg(Arg::new("lang")
+            .long("lang")
+            .value_name(t!("cli.lang_value"))
+            .help(t!("cli.lang")))
+        .arg(Arg::new("input")
+            .long("input")
+            .value_parser(["menu", "keys"])
+            .help(t!("cli.input")))
+        .arg(Arg::new("cards")
+            .long("cards")
+            .value_parser(["plain", "unicode", "ascii", "spoken"])
+            .help(t!("cli.cards")))
+        .arg(Arg::new("accessible")
+            .long("accessible")
+            .action(clap::ArgAction::SetTrue)
+            .help(t!("cli.accessible")))
+        .arg(Arg::new("preset")
+            .long("preset")
+            .value_parser(PossibleValuesParser::new(Preset::ALL.map(|p| p.key())))
+            .help(t!("cli.preset")))
+        .arg(Arg::new("bet")
+            .long("bet")
+            .value_parser(value_parser!(u32))
+            .help(t!("cli.bet")))
+        .arg(Arg::new("decks")
+            .long("decks")
+            .value_parser(value_parser!(u8))
+            .help(t!("cli.decks")))
+        .arg(Arg::new("bank")
+            .long("bank")
+            .value_parser(value_parser!(u32))
+            .help(t!("cli.bank")))
+        .arg(Arg::new("seed")
+            .long("seed")
+            .value_parser(value_parser!(u64))
+            .help(t!("cli.seed")))
+        .subcommand(Command::new("simulate")
+            .about(t!("cli.simulate"))
+            .arg(Arg::new("sessions")
+                .long("sessions")
+                .value_parser(value_parser!(u32))
+                .default_value("200")
+                .help(t!("cli.simulate_sessions")))
+            .arg(Arg::new("rounds")
+                .long("rounds")
+                .value_parser(value_parser!(u32))
+                .default_value("200")
+                .help(t!("cli.simulate_rounds")))
+            .arg(Arg::new("seed")
+                .long("seed")
+                .value_parser(value_parser!(u64))
+                .help(t!("cli.random_seed")))
+            .arg(Arg::new("system")
+                .long("system")
+                .value_parser(PossibleValuesParser::new(BettingSystem::ALL.map(|s| s.key())))
+                .help(t!("cli.simulate_system")))
+            .arg(Arg::new("advisor")
+                .long("advisor")
+                .value_parser(["ramp", "kelly"])
+                .conflicts_with("system")
+                .help(t!("cli.simulate_advisor"))))
+        .subcommand(Command::new("bankroll")
+            .about(t!("cli.bankroll"))
+            .arg(Arg::new("win-rate")
+                .long("win-rate")
+                .value_parser(value_parser!(f64))
+                .requires("std-dev")
+                .allow_negative_numbers(true)
+                .help(t!("cli.win_rate")))
+            .arg(Arg::new("std-dev")
+                .long("std-dev")
+                .value_parser(value_parser!(f64))
+                .requires("win-rate")
+                .help(t!("cli.std_dev")))
+            .arg(Arg::new("bankroll")
+                .long("bankroll")
+                .value_parser(value_parser!(u32))
+                .help(t!("cli.bankroll_amount")))
+            .arg(Arg::new("target-ror")
+                .long("target-ror")
+                .value_parser(value_parser!(f64))
+                .default_value("5")
+                .help(t!("cli.target_ror")))
+            .arg(Arg::new("advisor")
+                .long("advisor")
+                .value_parser(["off", "ramp", "kelly"])
+                .help(t!("cli.bankroll_advisor")))
+            .arg(Arg::new("max-units")
+                .long("max-units")
+                .value_parser(value_parser!(u32))
+                .help(t!("cli.max_units")))
+            .arg(Arg::new("units-per-count")
+                .long("units-per-count")
+                .value_parser(value_parser!(f32))
+                .help(t!("cli.units_per_count")))
+            .arg(Arg::new("sessions")
+                .long("sessions")
+                .value_parser(value_parser!(u32))
+                .default_value("500")
+                .help(t!("cli.bankroll_sessions")))
+            .arg(Arg::new("hands")
+                .long("hands")
+                .value_parser(value_parser!(u32))
+                .default_value("1000")
+                .help(t!("cli.bankroll_hands")))
+            .arg(Arg::new("seed")
+                .long("seed")
+                .value_parser(value_parser!(u64))
+                .help(t!("cli.random_seed"))))
+        .subcommand(Command::new("serve")
+            .about(t!("cli.serve"))
+            .arg(Arg::new("addr")
+                .long("addr")
+                .default_value("127.0.0.1:7777")
+                .help(t!("cli.listen_addr")))
+            .arg(Arg::new("timeout")
+                .long("timeout")
+                .value_parser(value_parser!(u64))
+                .default_value("30")
+                .help(t!("cli.timeout")))
+            .arg(Arg::new("round-gap")
+                .long("round-gap")
+                .value_parser(value_parser!(u64))
+                .default_value("3")
+                .help(t!("cli.round_gap"))))
+        .subcommand(Command::new("config")
+            .about(t!("cli.config_command"))
+            .subcommand_required(true)
+            .subcommand(Command::new("check")
+                .about(t!("cli.config_check")))
+            .subcommand(Command::new("show")
+                .about(t!("cli.config_show"))))
+        .subcommand(Command::new("join")
+            .about(t!("cli.join"))
+            .arg(Arg::new("addr")
+                .long("addr")
+                .default_value("127.0.0.1:7777")
+                .help(t!("cli.join_addr")))
+            .arg(Arg::new("name")
+                .long("name")
+                .default_value(t!("game.player"))
+                .help(t!("cli.join_name"))));
+    #[cfg(feature = "http")]
+    let cli = cli.subcommand(Command::new("http")
+        .about(t!("cli.http"))
+        .arg(Arg::new("addr")
+            .long("addr")
+            .default_value("127.0.0.1:8080")
+            .help(t!("cli.listen_addr")))
+        .arg(Arg::new("store")
+            .long("store")
+            .value_name(t!("cli.dir_value"))
+            .help(t!("cli.store"))));
+    #[cfg(feature = "spectator")]
+    let cli = cli.mut_subcommand("serve", |serve| serve
+        .arg(Arg::new("spectate")
+            .long("spectate")
+            .value_name(t!("cli.addr_value"))
+            .help(t!("cli.spectate"))));
+    #[cfg(feature = "tui")]
+    let cli = cli.arg(Arg::new("tui")
+        .long("tui")
+        .action(clap::ArgAction::SetTrue)
+        .help(t!("cli.tui")));
+    let matches = cli.get_matches();
 
-    let config_path = matches.get_one::<String>("config").unwrap();
-    let cfg = GameConfig::load(Path::new(config_path))?;
+    if let Some(lang) = matches.get_one::<String>("lang") {
+        i18n::set_language(lang)?;
+    }
+    // without --config a missing game_config.toml means the defaults
+    let project = (matches.value_source("config") != Some(ValueSource::DefaultValue))
+        .then(|| PathBuf::from(matches.get_one::<String>("config").unwrap()));
+    let inputs = config_layers::inputs(project, cli_entries(&matches));
+    match matches.subcommand_matches("config").and_then(|sub| sub.subcommand()) {
+        Some(("check", _)) => return check_config(&inputs, matches.contains_id("lang")),
+        Some(("show", _)) => return show_config(&inputs, matches.contains_id("lang")),
+        _ => {}
+    }
+    let mut cfg = GameConfig::load_layers(&inputs)?;
+    i18n::set_language(&cfg.lang)?;
+    if cfg.accessible {
+        cfg.card_style = CardStyle::Spoken;
+    }
+    card::set_style(cfg.card_style);
+    i18n::set_spoken(cfg.accessible);
+    if let Some(names) = matches.get_many::<String>("players") {
+        cfg.table.seat_humans(&names.cloned().collect::<Vec<_>>())?;
+    }
 
-    if matches.get_flag("direct") {
+    #[cfg(feature = "http")]
+    if let Some(sub) = matches.subcommand_matches("http") {
+        let opts = http_api::HttpOptions {
+            addr: sub.get_one::<String>("addr").unwrap().clone(),
+            store_dir: sub.get_one::<String>("store").map(std::path::PathBuf::from),
+        };
+        return http_api::run_http(&cfg, &opts);
+    }
+
+    #[cfg(feature = "tui")]
+    if matches.get_flag("tui") && matches.subcommand().is_none() {
+        if cfg.accessible {
+            anyhow::bail!(t!("error.tui_accessible"));
+        }
+        return tui::run_tui(&cfg, matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random));
+    }
+
+    if let Some(sub) = matches.subcommand_matches("simulate") {
+        run_simulation(&cfg, sub);
+    } else if let Some(sub) = matches.subcommand_matches("bankroll") {
+        run_bankroll(cfg, sub);
+    } else if let Some(sub) = matches.subcommand_matches("serve") {
+        let opts = ServeOptions {
+            addr: sub.get_one::<String>("addr").unwrap().clone(),
+            timeout: Duration::from_secs(*sub.get_one::<u64>("timeout").unwrap()),
+            round_gap: Duration::from_secs(*sub.get_one::<u64>("round-gap").unwrap()),
+        };
+        server::run_server(&cfg, &opts, serve_sinks(sub)?)?;
+    } else if let Some(sub) = matches.subcommand_matches("join") {
+        client::run_client(sub.get_one::<String>("addr").unwrap(), sub.get_one::<String>("name").unwrap())?;
+    } else if matches.get_one::<String>("protocol").unwrap() == "jsonl" {
+        jsonl::run_jsonl(&cfg, matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random))?;
+    } else if matches.get_flag("direct") {
         // Direct game mode
-        game::run_game(&cfg)?;
+        let seed = matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random);
+        let (mut session, _) = session::open(&cfg, seed)?;
+        session.exit_on_interrupt()?;
+        menu::print_rules(&session.cfg);
+        match session.run() {
+            // the session has said it was saved
+            Err(e) if prompt::is_interrupted(&e) => {}
+            result => result?,
+        }
     } else {
         // Menu mode (default)
         game::run_menu_loop(&cfg)?;
@@ -39,3 +287,171 @@ fn main() -> anyhow::Result<()> {
     
     Ok(())
 }
+
+/// Flags that override config keys: the top layer, above the environment.
+/// `--lang` picked out ahead of the full parse.
+fn early_lang() -> Option<String> {
+    let matches = Command::new("blackjack")
+        .ignore_errors(true)
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .allow_external_subcommands(true)
+        .arg(Arg::new("lang").long("lang"))
+        .try_get_matches()
+        .ok()?;
+    matches.get_one::<String>("lang").cloned()
+}
+
+fn cli_entries(matches: &ArgMatches) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut set = |flag: &str, key: &str, value: toml::Value| {
+        entries.push(Entry { key: key.to_string(), origin: format!("--{}", flag), value });
+    };
+    if let Some(&bet) = matches.get_one::<u32>("bet") {
+        set("bet", "bet_amount", i64::from(bet).into());
+    }
+    if let Some(&bank) = matches.get_one::<u32>("bank") {
+        set("bank", "player_starting_bank", i64::from(bank).into());
+    }
+    if let Some(&decks) = matches.get_one::<u8>("decks") {
+        set("decks", "rules.decks", i64::from(decks).into());
+    }
+    let strings = [("preset", "preset"), ("lang", "lang"), ("input", "input_mode"), ("cards", "card_style")];
+    for (flag, key) in strings {
+        if let Some(value) = matches.get_one::<String>(flag) {
+            set(flag, key, value.as_str().into());
+        }
+    }
+    if matches.get_flag("accessible") {
+        set("accessible", "accessible", true.into());
+    }
+    entries
+}
+
+/// Checks the layers, switching to the language they select unless --lang
+/// was given.
+fn check_layers(inputs: &[Input], lang_given: bool) -> config_check::Checked {
+    let checked = config_check::check_layers(inputs);
+    if let Some(cfg) = checked.config.as_ref().filter(|_| !lang_given)
+        && cfg.lang != i18n::language()
+        && i18n::set_language(&cfg.lang).is_ok()
+    {
+        return config_check::check_layers(inputs);
+    }
+    checked
+}
+
+/// The files read, for summaries; the defaults when there were none.
+fn files_label(checked: &config_check::Checked) -> String {
+    if checked.files.is_empty() {
+        config_layers::Layer::Default.label()
+    } else {
+        checked.files.join(", ")
+    }
+}
+
+/// `config check`: every problem in every layer, not just the first.
+fn check_config(inputs: &[Input], lang_given: bool) -> anyhow::Result<()> {
+    let checked = check_layers(inputs, lang_given);
+    for problem in &checked.problems {
+        println!("{}", problem.render());
+    }
+    let path = files_label(&checked);
+    let errors = checked.errors().count();
+    let summary = t!("config.check_summary", path = &path, errors = errors, warnings = checked.warnings().count());
+    if errors > 0 {
+        anyhow::bail!(summary);
+    }
+    if checked.problems.is_empty() {
+        println!("{}", t!("config.check_ok", path = path));
+    } else {
+        println!("{}", summary);
+    }
+    Ok(())
+}
+
+/// `config show`: each effective value and the layer it came from.
+fn show_config(inputs: &[Input], lang_given: bool) -> anyhow::Result<()> {
+    let checked = check_layers(inputs, lang_given);
+    for problem in &checked.problems {
+        eprintln!("{}", problem.render());
+    }
+    let rows = config_layers::show(&checked);
+    let key_width = rows.iter().map(|(key, _, _)| key.chars().count()).max().unwrap_or(0);
+    for (key, value, source) in &rows {
+        let pad = " ".repeat(key_width - key.chars().count());
+        println!("{}{} = {}  # {}", key, pad, value, source);
+    }
+    if checked.has_errors() {
+        let errors = checked.errors().count();
+        anyhow::bail!(t!("config.check_summary", path = files_label(&checked), errors = errors, warnings = checked.warnings().count()));
+    }
+    Ok(())
+}
+
+fn run_simulation(cfg: &GameConfig, matches: &ArgMatches) {
+    let opts = SimOptions {
+        sessions: *matches.get_one::<u32>("sessions").unwrap(),
+        rounds: *matches.get_one::<u32>("rounds").unwrap(),
+        seed: matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random),
+    };
+    if let Some(mode) = matches.get_one::<String>("advisor") {
+        let mode = if mode == "kelly" { AdvisorMode::Kelly } else { AdvisorMode::Ramp };
+        let report = simulation::simulate_spread(cfg, mode, &opts);
+        simulation::print_spread_report(cfg, &opts, &report);
+        return;
+    }
+    let systems: Vec<BettingSystem> = match matches.get_one::<String>("system") {
+        Some(key) => BettingSystem::from_key(key).into_iter().collect(),
+        None => BettingSystem::ALL.to_vec(),
+    };
+    let reports: Vec<_> = systems
+        .into_iter()
+        .map(|system| simulation::simulate_system(cfg, system, &opts))
+        .collect();
+    simulation::print_reports(cfg, &opts, &reports);
+}
+
+fn run_bankroll(mut cfg: GameConfig, matches: &ArgMatches) {
+    if let Some(&units) = matches.get_one::<u32>("max-units") {
+        cfg.bet_advisor.max_units = units;
+    }
+    if let Some(&units) = matches.get_one::<f32>("units-per-count") {
+        cfg.bet_advisor.units_per_count = units;
+    }
+    let mode = matches
+        .get_one::<String>("advisor")
+        .and_then(|key| AdvisorMode::from_key(key))
+        .unwrap_or(cfg.bet_advisor.mode);
+    let given = matches
+        .get_one::<f64>("win-rate")
+        .copied()
+        .zip(matches.get_one::<f64>("std-dev").copied());
+
+    let query = BankrollQuery {
+        bankroll: matches.get_one::<u32>("bankroll").copied().unwrap_or(cfg.player_starting_bank),
+        target_ror: matches.get_one::<f64>("target-ror").unwrap() / 100.0,
+        mode,
+        given,
+        sim: SimOptions {
+            sessions: *matches.get_one::<u32>("sessions").unwrap(),
+            rounds: *matches.get_one::<u32>("hands").unwrap(),
+            seed: matches.get_one::<u64>("seed").copied().unwrap_or_else(rand::random),
+        },
+    };
+    bankroll::run_analysis(&cfg, &query);
+}
+
+/// Extra listeners for the table's events (`serve --spectate`).
+#[cfg(feature = "spectator")]
+fn serve_sinks(matches: &ArgMatches) -> anyhow::Result<Vec<Box<dyn events::EventSink>>> {
+    match matches.get_one::<String>("spectate") {
+        Some(addr) => Ok(vec![Box::new(spectator::SpectatorFeed::bind(addr)?)]),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(not(feature = "spectator"))]
+fn serve_sinks(_matches: &ArgMatches) -> anyhow::Result<Vec<Box<dyn events::EventSink>>> {
+    Ok(Vec::new())
+}
@@ -1,9 +1,16 @@
-use dialoguer::{Select, theme::ColorfulTheme};
+use crate::advisor::advised_bet;
+use crate::config::GameConfig;
+use crate::player::PlayerState;
+use crate::prompt;
+use crate::rules::Preset;
+use crate::t;
 use std::io;
 
 #[derive(Debug, Clone)]
 pub enum MenuChoice {
     StartGame,
+    Trainer,
+    Rules,
     ShowHelp,
     Quit,
 }
@@ -20,14 +27,16 @@ impl MenuChoice {
     pub fn from_index(index: usize) -> Option<Self> {
         match index {
             0 => Some(MenuChoice::StartGame),
-            1 => Some(MenuChoice::ShowHelp),
-            2 => Some(MenuChoice::Quit),
+            1 => Some(MenuChoice::Trainer),
+            2 => Some(MenuChoice::Rules),
+            3 => Some(MenuChoice::ShowHelp),
+            4 => Some(MenuChoice::Quit),
             _ => None,
         }
     }
 
-    pub fn menu_items() -> Vec<&'static str> {
-        vec!["ゲーム開始", "ヘルプ表示", "終了"]
+    pub fn menu_items() -> Vec<String> {
+        vec![t!("menu.start"), t!("menu.trainer"), t!("menu.rules"), t!("menu.help"), t!("menu.quit")]
     }
 }
 
@@ -42,37 +51,102 @@ impl PostGameChoice {
         }
     }
 
-    pub fn menu_items() -> Vec<&'static str> {
-        vec!["次のラウンド", "賭け金変更", "設定", "メインメニューに戻る"]
+    pub fn menu_items() -> Vec<String> {
+        vec![t!("menu.next_round"), t!("menu.change_bet"), t!("menu.settings"), t!("menu.main_menu")]
     }
 }
 
 pub fn display_help() {
-    println!("\n=== ブラックジャック ヘルプ ===");
-    println!("基本ルール:");
-    println!("• 目標: 21に可能な限り近づけ、21を超えないようにする");
-    println!("• 絵札（J、Q、K）は10ポイント");
-    println!("• エース（A）は1ポイントまたは11ポイント（有利な方を自動選択）");
-    println!("• ディーラーは16以下でヒット、17以上でスタンドする");
-    println!("\n操作方法:");
-    println!("• h/hit: カードを1枚引く");
-    println!("• s/stand: 現在の手札で勝負する");
-    println!("\nコマンドラインオプション:");
-    println!("• cargo run -- --direct   : メニューをスキップして直接ゲーム開始");
-    println!("• cargo run -- --config FILE : カスタム設定ファイルを使用");
-    println!("• cargo run -- --help     : コマンドヘルプを表示");
-    println!("\nEnterキーを押してメニューに戻る...");
+    println!("\n{}", t!("help.text"));
+    println!("\n{}", t!("help.press_enter"));
     let mut _dummy = String::new();
     let _ = io::stdin().read_line(&mut _dummy);
 }
 
-pub fn get_user_choice() -> Result<MenuChoice, Box<dyn std::error::Error>> {
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("オプションを選択してください:")
-        .default(0)
-        .items(MenuChoice::menu_items())
-        .interact()?;
+pub fn get_user_choice() -> anyhow::Result<MenuChoice> {
+    let selection = prompt::select(&t!("menu.prompt"), &MenuChoice::menu_items(), 0)?;
 
-    MenuChoice::from_index(selection)
-        .ok_or_else(|| "無効な選択です".into())
+    MenuChoice::from_index(selection).ok_or_else(|| anyhow::anyhow!(t!("menu.invalid")))
+}
+
+/// The rules in effect and their estimated house edge.
+pub fn print_rules(cfg: &GameConfig) {
+    let name = cfg.preset.map_or_else(|| t!("preset.default"), |p| p.label());
+    println!("{}", t!("rules.active", name = name, summary = cfg.rules.summary()));
+    println!("{}", t!("rules.house_edge", edge = format!("{:.2}", cfg.rules.house_edge())));
+}
+
+/// Preset picker listing each rule set's house edge; `None` keeps the rules.
+pub fn pick_preset(cfg: &GameConfig) -> anyhow::Result<Option<Preset>> {
+    let mut items: Vec<String> = Preset::ALL
+        .iter()
+        .map(|&p| t!("rules.item", name = p.label(), edge = format!("{:.2}", cfg.preset_rules(Some(p)).house_edge())))
+        .collect();
+    items.push(t!("menu.back"));
+    let current = cfg.preset.and_then(|p| Preset::ALL.iter().position(|&q| q == p)).unwrap_or(items.len() - 1);
+    let selection = prompt::select(&t!("rules.prompt"), &items, current)?;
+    Ok(Preset::ALL.get(selection).copied())
+}
+
+pub enum BetChoice {
+    /// new base unit for the betting system
+    Unit(u32),
+    /// one-off bet for the next round only
+    NextRound(u32),
+}
+
+/// Bet picker; `None` keeps the betting system's suggestion.
+pub fn pick_bet(cfg: &GameConfig, player: &PlayerState, true_count: f32) -> anyhow::Result<Option<BetChoice>> {
+    let max = cfg.table_max_bet.min(player.bank);
+    let amounts: Vec<u32> = [10, 25, 50, 100]
+        .into_iter()
+        .filter(|a| (cfg.table_min_bet..=max).contains(a))
+        .collect();
+    let mode = cfg.bet_advisor.mode;
+    let advised = advised_bet(cfg, mode, true_count, player.bank);
+
+    println!("{}", t!("bet.current", bet = player.bet, currency = cfg.currency_name));
+    println!("{}", t!("bet.bank", bank = player.bank, currency = cfg.currency_name));
+
+    let mut items = vec![t!(
+        "bet.suggested",
+        bet = player.suggested_bet(cfg),
+        currency = cfg.currency_name,
+        system = cfg.betting_system.label()
+    )];
+    if let Some(amount) = advised {
+        items.push(t!(
+            "bet.advised",
+            bet = amount,
+            currency = cfg.currency_name,
+            mode = mode.label(),
+            tc = format!("{:+.1}", true_count)
+        ));
+    }
+    let fixed_start = items.len();
+    items.extend(amounts.iter().map(|a| format!("{}{}", a, cfg.currency_name)));
+    items.push(t!("bet.custom"));
+    items.push(t!("menu.back"));
+
+    let selection = prompt::select(&t!("bet.prompt"), &items, 0)?;
+
+    if selection == 0 || selection == items.len() - 1 {
+        return Ok(None);
+    }
+    if let (Some(amount), 1) = (advised, selection) {
+        return Ok(Some(BetChoice::NextRound(amount)));
+    }
+    if let Some(&amount) = amounts.get(selection - fixed_start) {
+        return Ok(Some(BetChoice::Unit(amount)));
+    }
+
+    let min = cfg.table_min_bet;
+    let amount = prompt::number(&t!("bet.input", min = min, max = max, currency = cfg.currency_name), None, |v| {
+        if (min..=max).contains(v) {
+            Ok(())
+        } else {
+            Err(t!("bet.out_of_range", min = min, max = max))
+        }
+    })?;
+    Ok(Some(BetChoice::Unit(amount)))
 }
@@ -0,0 +1,69 @@
+use crate::betting::{BetProgression, BettingSystem, RoundResult, capped_bet};
+use crate::config::GameConfig;
+use serde::{Deserialize, Serialize};
+
+/// A `PlayerState` as written to disk between launches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedPlayer {
+    pub bank: u32,
+    pub unit: u32,
+    pub bet: u32,
+    /// the betting system's position (`BetProgression::state`)
+    #[serde(default)]
+    pub progression: Vec<i64>,
+}
+
+/// Bankroll and wager carried from one round to the next.
+pub struct PlayerState {
+    pub bank: u32,
+    pub bet: u32,
+    unit: u32,
+    progression: Box<dyn BetProgression>,
+}
+
+impl PlayerState {
+    pub fn with_system(cfg: &GameConfig, system: BettingSystem) -> Self {
+        let mut state = Self {
+            bank: cfg.player_starting_bank,
+            bet: cfg.bet_amount,
+            unit: cfg.bet_amount,
+            progression: system.build(),
+        };
+        state.bet = state.suggested_bet(cfg);
+        state
+    }
+
+    /// A bankroll saved earlier, with the betting system where it stood.
+    pub fn resume(system: BettingSystem, saved: &SavedPlayer) -> Self {
+        let mut state = Self { bank: saved.bank, bet: saved.bet, unit: saved.unit, progression: system.build() };
+        state.progression.restore(&saved.progression);
+        state
+    }
+
+    pub fn saved(&self) -> SavedPlayer {
+        SavedPlayer { bank: self.bank, unit: self.unit, bet: self.bet, progression: self.progression.state() }
+    }
+
+    /// Wager proposed by the configured betting system.
+    pub fn suggested_bet(&self, cfg: &GameConfig) -> u32 {
+        capped_bet(self.progression.units(), self.unit, cfg, self.bank)
+    }
+
+    /// Uses `amount` as the new base unit and restarts the progression.
+    pub fn set_unit(&mut self, amount: u32, cfg: &GameConfig) {
+        self.unit = amount;
+        self.progression.reset();
+        self.bet = self.suggested_bet(cfg);
+    }
+
+    /// Applies a round result and lets the betting system pick the next bet.
+    pub fn settle(&mut self, net: i64, cfg: &GameConfig) {
+        self.bank = (i64::from(self.bank) + net).max(0) as u32;
+        self.progression.record(RoundResult::from_net(net));
+        self.bet = self.suggested_bet(cfg);
+    }
+
+    pub fn can_play(&self, cfg: &GameConfig) -> bool {
+        self.bank > 0 && self.bank >= cfg.table_min_bet
+    }
+}
@@ -0,0 +1,108 @@
+//! Terminal prompts. Normally dialoguer's arrow-key menus; in `--accessible`
+//! mode numbered lists answered by typing a line, with no cursor movement
+//! or color so screen readers can follow along.
+
+use crate::i18n::spoken;
+use crate::t;
+use dialoguer::{Input, Select, theme::ColorfulTheme};
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+
+/// Index of the chosen item.
+pub fn select<T: Display>(prompt: &str, items: &[T], default: usize) -> anyhow::Result<usize> {
+    if !spoken() {
+        return Ok(Select::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(default)
+            .items(items)
+            .interact()?);
+    }
+    println!("{}", prompt);
+    for (i, item) in items.iter().enumerate() {
+        println!("{}. {}", i + 1, item);
+    }
+    loop {
+        let line = read_line(&t!("prompt.number", max = items.len(), default = default + 1))?;
+        if line.is_empty() {
+            return Ok(default);
+        }
+        match line.parse::<usize>() {
+            Ok(n) if (1..=items.len()).contains(&n) => return Ok(n - 1),
+            _ => println!("{}", t!("prompt.bad_number", max = items.len())),
+        }
+    }
+}
+
+/// A number checked by `validate`, which returns the message to show on a bad value.
+pub fn number(prompt: &str, default: Option<u32>, validate: impl Fn(&u32) -> Result<(), String>) -> anyhow::Result<u32> {
+    if !spoken() {
+        let theme = ColorfulTheme::default();
+        let mut input = Input::<u32>::with_theme(&theme).with_prompt(prompt);
+        if let Some(default) = default {
+            input = input.default(default);
+        }
+        return Ok(input.validate_with(|v: &u32| validate(v)).interact_text()?);
+    }
+    loop {
+        let label = match default {
+            Some(default) => t!("prompt.number_default", prompt = prompt, default = default),
+            None => prompt.to_string(),
+        };
+        let line = read_line(&label)?;
+        let value = match (line.parse::<u32>(), default) {
+            (Ok(value), _) => value,
+            (Err(_), Some(default)) if line.is_empty() => default,
+            _ => {
+                println!("{}", t!("prompt.not_a_number"));
+                continue;
+            }
+        };
+        match validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+/// A line of text checked by `validate`; an empty answer keeps `default`.
+pub fn text(prompt: &str, default: &str, validate: impl Fn(&String) -> Result<(), String>) -> anyhow::Result<String> {
+    if !spoken() {
+        let theme = ColorfulTheme::default();
+        return Ok(Input::<String>::with_theme(&theme)
+            .with_prompt(prompt)
+            .default(default.to_string())
+            .validate_with(|v: &String| validate(v))
+            .interact_text()?);
+    }
+    loop {
+        let line = read_line(&t!("prompt.text_default", prompt = prompt, default = default))?;
+        let value = if line.is_empty() { default.to_string() } else { line };
+        match validate(&value) {
+            Ok(()) => return Ok(value),
+            Err(message) => println!("{}", message),
+        }
+    }
+}
+
+/// Whether `error` is a prompt being interrupted (Ctrl+C, or stdin closing
+/// in `--accessible` mode) rather than a failure.
+pub fn is_interrupted(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        let io = match cause.downcast_ref::<dialoguer::Error>() {
+            Some(dialoguer::Error::IO(io)) => Some(io),
+            None => cause.downcast_ref::<io::Error>(),
+        };
+        io.is_some_and(|io| io.kind() == io::ErrorKind::Interrupted)
+    })
+}
+
+/// One trimmed line from stdin; a closed stdin counts as an interruption.
+fn read_line(prompt: &str) -> anyhow::Result<String> {
+    print!("{}: ", prompt);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Err(io::Error::from(io::ErrorKind::Interrupted).into());
+    }
+    Ok(line.trim().to_string())
+}
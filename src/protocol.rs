@@ -0,0 +1,115 @@
+use crate::blackjack::HandOutcome;
+use crate::card::{Card, hand_value};
+use crate::game_action::GameAction;
+use crate::round::Round;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// Line-delimited JSON sent by a `join` client (see docs/protocol.md).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Hello { name: String },
+    Bet { amount: u32 },
+    Insurance { take: bool },
+    Action { action: GameAction },
+    Leave,
+}
+
+/// Line-delimited JSON sent by the `serve` table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Welcome { seat: usize, bank: u32, currency: String, timeout_secs: u64 },
+    BetRequest { min: u32, max: u32, suggested: u32 },
+    InsuranceRequest { cost: u32 },
+    ActionRequest { hand: usize, cards: Vec<Card>, value: u8, legal: Vec<GameAction> },
+    State(TableView),
+    Event { text: String },
+    Result(RoundResultView),
+    Error { message: String },
+}
+
+/// The table as every seat may see it: the hole card stays `null` until revealed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableView {
+    pub dealer: Vec<Option<Card>>,
+    pub seats: Vec<SeatView>,
+    pub shoe_remaining: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatView {
+    /// the number given in `welcome`
+    pub seat: usize,
+    pub name: String,
+    pub hands: Vec<HandView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandView {
+    pub cards: Vec<Card>,
+    pub value: u8,
+    pub bet: u32,
+    pub doubled: bool,
+    pub surrendered: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundResultView {
+    pub dealer: Vec<Card>,
+    pub dealer_value: u8,
+    pub seats: Vec<SeatResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeatResult {
+    pub seat: usize,
+    pub name: String,
+    pub outcomes: Vec<HandOutcome>,
+    pub net: i64,
+    pub bank: u32,
+}
+
+impl TableView {
+    /// `seats` are the seat numbers and names in spot order.
+    pub fn new(round: &Round, seats: &[(usize, &str)], shoe_remaining: usize) -> Self {
+        let hidden = !round.hole_revealed();
+        let dealer = round
+            .dealer
+            .iter()
+            .enumerate()
+            .map(|(i, card)| (i > 0 || !hidden).then_some(*card))
+            .collect();
+        let seats = round
+            .spots
+            .iter()
+            .zip(seats)
+            .map(|(spot, &(seat, name))| SeatView {
+                seat,
+                name: name.to_string(),
+                hands: spot
+                    .hands
+                    .iter()
+                    .map(|h| HandView {
+                        cards: h.cards.clone(),
+                        value: hand_value(&h.cards),
+                        bet: h.bet,
+                        doubled: h.doubled,
+                        surrendered: h.surrendered,
+                    })
+                    .collect(),
+            })
+            .collect();
+        Self { dealer, seats, shoe_remaining }
+    }
+}
+
+/// Writes one message as a single JSON line.
+pub fn send<T: Serialize>(out: &mut impl Write, message: &T) -> anyhow::Result<()> {
+    let mut line = serde_json::to_string(message)?;
+    line.push('\n');
+    out.write_all(line.as_bytes())?;
+    out.flush()?;
+    Ok(())
+}
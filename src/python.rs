@@ -0,0 +1,281 @@
+//! Python extension module `veintiuna`: the same rules the game plays, for
+//! notebooks. Cards are rank and suit letter ("10H", "AS"; a rank alone is
+//! a spade) and actions their snake_case names, as in the JSON protocols.
+
+use crate::advisor::AdvisorMode;
+use crate::bankroll::GameStats;
+use crate::betting::BettingSystem;
+use crate::card::{Card, hand_value};
+use crate::config::GameConfig;
+use crate::env::BlackjackEnv;
+use crate::game_action::GameAction;
+use crate::round::{Round, Wager};
+use crate::shoe::Shoe;
+use crate::simulation::{self, SimOptions};
+use crate::strategy::{basic_strategy, is_soft};
+use crate::t;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Parses a protocol name ("A", "double_down", "flat", ...) into `T`.
+fn parse<T: DeserializeOwned>(name: &str) -> PyResult<T> {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .map_err(|e| PyValueError::new_err(format!("{}: {}", name, e)))
+}
+
+fn parse_cards(cards: Vec<String>) -> PyResult<Vec<Card>> {
+    cards.iter().map(|c| parse(c)).collect()
+}
+
+fn names<T: Serialize>(items: &[T]) -> Vec<String> {
+    items
+        .iter()
+        .map(|item| match serde_json::to_value(item) {
+            Ok(serde_json::Value::String(name)) => name,
+            _ => String::new(),
+        })
+        .collect()
+}
+
+/// Converts through JSON so Python gets plain dicts and lists.
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let text = serde_json::to_string(value).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+fn load_config(path: Option<&str>) -> PyResult<GameConfig> {
+    match path {
+        Some(path) => GameConfig::load(Path::new(path)).map_err(|e| PyValueError::new_err(e.to_string())),
+        None => Ok(GameConfig::default()),
+    }
+}
+
+fn runtime(e: anyhow::Error) -> PyErr {
+    PyRuntimeError::new_err(e.to_string())
+}
+
+#[pyfunction(name = "hand_value")]
+fn py_hand_value(cards: Vec<String>) -> PyResult<u8> {
+    Ok(hand_value(&parse_cards(cards)?))
+}
+
+#[pyfunction(name = "is_soft")]
+fn py_is_soft(cards: Vec<String>) -> PyResult<bool> {
+    Ok(is_soft(&parse_cards(cards)?))
+}
+
+/// Basic strategy play; `legal` defaults to every action.
+#[pyfunction(name = "basic_strategy")]
+#[pyo3(signature = (cards, dealer_up, legal = None))]
+fn py_basic_strategy(cards: Vec<String>, dealer_up: &str, legal: Option<Vec<String>>) -> PyResult<String> {
+    let legal: Vec<GameAction> = match legal {
+        Some(legal) => legal.iter().map(|a| parse(a)).collect::<PyResult<_>>()?,
+        None => crate::env::ACTIONS.to_vec(),
+    };
+    let action = basic_strategy(&parse_cards(cards)?, parse(dealer_up)?, &legal);
+    Ok(names(&[action]).remove(0))
+}
+
+/// Ruin rate and return of one betting system (`simulate` subcommand).
+#[pyfunction]
+#[pyo3(signature = (system = "flat", sessions = 200, rounds = 200, seed = None, config = None))]
+fn simulate(
+    py: Python<'_>,
+    system: &str,
+    sessions: u32,
+    rounds: u32,
+    seed: Option<u64>,
+    config: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    let cfg = load_config(config)?;
+    let system = BettingSystem::from_key(system).ok_or_else(|| PyValueError::new_err(t!("python.unknown_system", system = system)))?;
+    let opts = SimOptions { sessions, rounds, seed: seed.unwrap_or_else(rand::random) };
+    to_py(py, &simulation::simulate_system(&cfg, system, &opts))
+}
+
+/// Win rate and standard deviation per 100 hands with a count-based spread.
+#[pyfunction]
+#[pyo3(signature = (mode = "ramp", sessions = 200, rounds = 200, seed = None, config = None))]
+fn simulate_spread(
+    py: Python<'_>,
+    mode: &str,
+    sessions: u32,
+    rounds: u32,
+    seed: Option<u64>,
+    config: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    let cfg = load_config(config)?;
+    let mode = AdvisorMode::from_key(mode).ok_or_else(|| PyValueError::new_err(t!("python.unknown_mode", mode = mode)))?;
+    let opts = SimOptions { sessions, rounds, seed: seed.unwrap_or_else(rand::random) };
+    to_py(py, &simulation::simulate_spread(&cfg, mode, &opts))
+}
+
+/// Risk of ruin for a win rate and standard deviation per 100 hands.
+#[pyfunction]
+fn risk_of_ruin(win_rate: f64, std_dev: f64, bankroll: f64) -> f64 {
+    GameStats::per_100(win_rate, std_dev).risk_of_ruin(bankroll)
+}
+
+/// Bankroll needed for the target risk of ruin; `None` without an edge.
+#[pyfunction]
+fn bankroll_for(win_rate: f64, std_dev: f64, target_ror: f64) -> Option<f64> {
+    GameStats::per_100(win_rate, std_dev).bankroll_for(target_ror)
+}
+
+#[pyclass(name = "Shoe")]
+struct PyShoe {
+    shoe: Shoe,
+}
+
+#[pymethods]
+impl PyShoe {
+    #[new]
+    #[pyo3(signature = (decks = 6, penetration = 0.75, seed = None))]
+    fn new(decks: u8, penetration: f32, seed: Option<u64>) -> Self {
+        Self { shoe: Shoe::new(decks, penetration, seed.unwrap_or_else(rand::random)) }
+    }
+
+    fn draw(&mut self) -> String {
+        names(&[self.shoe.draw()]).remove(0)
+    }
+
+    fn shuffle(&mut self) {
+        self.shoe.shuffle();
+    }
+
+    fn needs_shuffle(&self) -> bool {
+        self.shoe.needs_shuffle()
+    }
+
+    fn remaining(&self) -> usize {
+        self.shoe.remaining()
+    }
+
+    fn true_count(&self) -> f32 {
+        self.shoe.true_count()
+    }
+}
+
+/// One round on the engine; every step that draws takes the shoe.
+#[pyclass(name = "Round")]
+struct PyRound {
+    round: Round,
+}
+
+#[pymethods]
+impl PyRound {
+    /// Deals one spot per bet under the rules of `config`; `bankroll`
+    /// covers doubles and splits.
+    #[staticmethod]
+    #[pyo3(signature = (shoe, bets, bankroll = None, config = None))]
+    fn deal(
+        mut shoe: PyRefMut<'_, PyShoe>,
+        bets: Vec<u32>,
+        bankroll: Option<u32>,
+        config: Option<&str>,
+    ) -> PyResult<Self> {
+        let cfg = load_config(config)?;
+        let wagers: Vec<Wager> =
+            bets.iter().map(|&bet| Wager { bet, bankroll: bankroll.unwrap_or(u32::MAX) }).collect();
+        Ok(Self { round: Round::deal(&mut shoe.shoe, &wagers, &cfg.rules) })
+    }
+
+    /// Spot that must answer insurance next.
+    fn pending_insurance(&self) -> Option<usize> {
+        self.round.pending_insurance()
+    }
+
+    fn resolve_insurance(&mut self, mut shoe: PyRefMut<'_, PyShoe>, take: bool) {
+        self.round.resolve_insurance(&mut shoe.shoe, take);
+    }
+
+    /// (spot, hand) to act next, or `None` when the players are done.
+    fn active(&self) -> Option<(usize, usize)> {
+        self.round.active()
+    }
+
+    fn legal_actions(&self) -> Vec<String> {
+        names(&self.round.legal_actions())
+    }
+
+    fn apply(&mut self, mut shoe: PyRefMut<'_, PyShoe>, action: &str) -> PyResult<()> {
+        self.round.apply(&mut shoe.shoe, parse(action)?).map_err(runtime)
+    }
+
+    fn play_dealer(&mut self, mut shoe: PyRefMut<'_, PyShoe>) {
+        self.round.play_dealer(&mut shoe.shoe);
+    }
+
+    /// Dealer's cards; the hole card is `None` until revealed.
+    fn dealer(&self) -> Vec<Option<String>> {
+        let hidden = !self.round.hole_revealed();
+        names(&self.round.dealer)
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| (i > 0 || !hidden).then_some(card))
+            .collect()
+    }
+
+    fn hands(&self, spot: usize) -> PyResult<Vec<Vec<String>>> {
+        let spot = self.round.spots.get(spot).ok_or_else(|| PyValueError::new_err(t!("python.bad_spot")))?;
+        Ok(spot.hands.iter().map(|h| names(&h.cards)).collect())
+    }
+
+    #[pyo3(signature = (spot, player_edge = 0.0))]
+    fn outcomes(&self, spot: usize, player_edge: f32) -> Vec<String> {
+        names(&self.round.outcomes(spot, player_edge))
+    }
+
+    #[pyo3(signature = (spot, player_edge = 0.0))]
+    fn net(&self, spot: usize, player_edge: f32) -> i64 {
+        self.round.net(spot, player_edge)
+    }
+}
+
+/// `BlackjackEnv`; observations and steps come back as dicts.
+#[pyclass(name = "Env")]
+struct PyEnv {
+    env: BlackjackEnv,
+}
+
+#[pymethods]
+impl PyEnv {
+    #[new]
+    #[pyo3(signature = (seed = None, config = None))]
+    fn new(seed: Option<u64>, config: Option<&str>) -> PyResult<Self> {
+        let cfg = load_config(config)?;
+        Ok(Self { env: BlackjackEnv::new(&cfg, seed.unwrap_or_else(rand::random)) })
+    }
+
+    #[pyo3(signature = (seed = None))]
+    fn reset(&mut self, py: Python<'_>, seed: Option<u64>) -> PyResult<Py<PyAny>> {
+        to_py(py, &self.env.reset(seed))
+    }
+
+    fn step(&mut self, py: Python<'_>, action: &str) -> PyResult<Py<PyAny>> {
+        let step = self.env.step(parse(action)?).map_err(runtime)?;
+        to_py(py, &step)
+    }
+
+    fn observation(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_py(py, &self.env.observation())
+    }
+}
+
+#[pymodule]
+fn veintiuna(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(py_hand_value, m)?)?;
+    m.add_function(wrap_pyfunction!(py_is_soft, m)?)?;
+    m.add_function(wrap_pyfunction!(py_basic_strategy, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_spread, m)?)?;
+    m.add_function(wrap_pyfunction!(risk_of_ruin, m)?)?;
+    m.add_function(wrap_pyfunction!(bankroll_for, m)?)?;
+    m.add_class::<PyShoe>()?;
+    m.add_class::<PyRound>()?;
+    m.add_class::<PyEnv>()?;
+    Ok(())
+}
@@ -0,0 +1,318 @@
+use crate::blackjack::{HandOutcome, dealer_turn, settle_hand};
+use crate::card::{Card, hand_value, is_blackjack};
+use crate::events::{SpotSettlement, TableEvent};
+use crate::game_action::{GameAction, can_double_down, can_split, can_surrender};
+use crate::rules::{HoleCard, Rules};
+use crate::shoe::Shoe;
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerHand {
+    pub cards: Vec<Card>,
+    pub bet: u32,
+    pub doubled: bool,
+    pub surrendered: bool,
+    pub from_split: bool,
+    done: bool,
+}
+
+impl PlayerHand {
+    fn new(cards: Vec<Card>, bet: u32, from_split: bool) -> Self {
+        Self { cards, bet, doubled: false, surrendered: false, from_split, done: false }
+    }
+
+    /// A natural blackjack; 21 on a split hand does not count.
+    pub fn is_blackjack(&self) -> bool {
+        !self.from_split && is_blackjack(&self.cards)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done || hand_value(&self.cards) >= 21
+    }
+}
+
+/// Opening wager of one betting spot.
+#[derive(Debug, Clone, Copy)]
+pub struct Wager {
+    pub bet: u32,
+    /// whole bankroll behind the bet, used to cover doubles and splits
+    pub bankroll: u32,
+}
+
+/// The hands played from one seat's betting spot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spot {
+    pub hands: Vec<PlayerHand>,
+    pub insurance: u32,
+    /// Bankroll still available for doubling and splitting.
+    funds: u32,
+    insurance_pending: bool,
+}
+
+/// One round between the seated players and the dealer, independent of any UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Round {
+    pub spots: Vec<Spot>,
+    pub dealer: Vec<Card>,
+    /// (spot, hand) to act next
+    active: (usize, usize),
+    hole_revealed: bool,
+    #[serde(default)]
+    rules: Rules,
+    /// what happened since the last `drain_events`
+    #[serde(skip)]
+    events: Vec<TableEvent>,
+}
+
+impl Round {
+    /// Deals to every spot in seat order, then the dealer.
+    pub fn deal(shoe: &mut Shoe, wagers: &[Wager], rules: &Rules) -> Self {
+        // initial deal: each spot, dealer (face down), each spot, dealer
+        let first: Vec<Card> = wagers.iter().map(|_| shoe.draw()).collect();
+        let hole = shoe.draw_face_down();
+        let second: Vec<Card> = wagers.iter().map(|_| shoe.draw()).collect();
+        let up = shoe.draw();
+
+        let spots: Vec<Spot> = wagers
+            .iter()
+            .zip(first.into_iter().zip(second))
+            .map(|(w, (a, b))| {
+                let funds = w.bankroll.saturating_sub(w.bet);
+                Spot {
+                    hands: vec![PlayerHand::new(vec![a, b], w.bet, false)],
+                    insurance: 0,
+                    funds,
+                    insurance_pending: up.is_ace() && w.bet >= 2 && funds >= w.bet / 2,
+                }
+            })
+            .collect();
+
+        let hands = spots.iter().map(|s| s.hands[0].cards.clone()).collect();
+        let events = vec![TableEvent::Deal { hands, dealer_up: up }];
+        let mut round =
+            Self { spots, dealer: vec![hole, up], active: (0, 0), hole_revealed: false, rules: rules.clone(), events };
+        if round.pending_insurance().is_none() {
+            round.peek(shoe);
+        }
+        round
+    }
+
+    pub fn dealer_has_blackjack(&self) -> bool {
+        is_blackjack(&self.dealer)
+    }
+
+    /// The dealer's face-up card (the first card is dealt face down).
+    pub fn dealer_up_card(&self) -> Card {
+        self.dealer[1]
+    }
+
+    /// Next spot that must answer the insurance offer; no hand acts until
+    /// every spot has answered.
+    pub fn pending_insurance(&self) -> Option<usize> {
+        self.spots.iter().position(|s| s.insurance_pending)
+    }
+
+    /// Takes or declines insurance (half the bet) for the pending spot; the
+    /// dealer peeks after the last answer.
+    pub fn resolve_insurance(&mut self, shoe: &mut Shoe, take: bool) {
+        let Some(index) = self.pending_insurance() else {
+            return;
+        };
+        let spot = &mut self.spots[index];
+        if take {
+            spot.insurance = spot.hands[0].bet / 2;
+            spot.funds -= spot.insurance;
+        }
+        spot.insurance_pending = false;
+        self.events.push(TableEvent::Insurance { spot: index, take });
+        if self.pending_insurance().is_none() {
+            self.peek(shoe);
+        }
+    }
+
+    /// Dealer checks for blackjack; the round ends at once if found. Without
+    /// a hole card (ENHC) the blackjack only shows once the players are done.
+    fn peek(&mut self, shoe: &mut Shoe) {
+        if self.rules.hole_card == HoleCard::Peek && self.dealer_has_blackjack() {
+            self.reveal_hole(shoe);
+            self.active = (self.spots.len(), 0);
+        }
+        self.advance();
+    }
+
+    /// Spot and hand index of the hand to act next.
+    pub fn active(&self) -> Option<(usize, usize)> {
+        (self.pending_insurance().is_none() && self.active.0 < self.spots.len()).then_some(self.active)
+    }
+
+    pub fn active_hand(&self) -> Option<&PlayerHand> {
+        self.active().map(|(s, h)| &self.spots[s].hands[h])
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn legal_actions(&self) -> Vec<GameAction> {
+        let Some((s, h)) = self.active() else {
+            return Vec::new();
+        };
+        let spot = &self.spots[s];
+        let hand = &spot.hands[h];
+        let mut actions = vec![GameAction::Hit, GameAction::Stand];
+        let affordable = spot.funds >= hand.bet;
+
+        if affordable && can_double_down(&hand.cards) && self.rules.can_double(&hand.cards, hand.from_split) {
+            actions.push(GameAction::DoubleDown);
+        }
+        if affordable && can_split(&hand.cards) && self.rules.can_split(&hand.cards, spot.hands.len(), hand.from_split) {
+            actions.push(GameAction::Split);
+        }
+        if self.rules.surrender && spot.hands.len() == 1 && can_surrender(&hand.cards) {
+            actions.push(GameAction::Surrender);
+        }
+        actions
+    }
+
+    pub fn apply(&mut self, shoe: &mut Shoe, action: GameAction) -> anyhow::Result<()> {
+        if !self.legal_actions().contains(&action) {
+            anyhow::bail!(t!("error.illegal_action", action = action.name()));
+        }
+        let (s, index) = self.active;
+        let spot = &mut self.spots[s];
+
+        match action {
+            GameAction::Hit => spot.hands[index].cards.push(shoe.draw()),
+            GameAction::Stand => spot.hands[index].done = true,
+            GameAction::DoubleDown => {
+                let hand = &mut spot.hands[index];
+                spot.funds -= hand.bet;
+                hand.bet *= 2;
+                hand.doubled = true;
+                hand.done = true;
+                hand.cards.push(shoe.draw());
+            }
+            GameAction::Split => {
+                let bet = spot.hands[index].bet;
+                spot.funds -= bet;
+                let second_card = spot.hands[index].cards.pop().unwrap();
+                let first_card = spot.hands[index].cards[0];
+
+                // Deal one card to each hand
+                let hand1 = vec![first_card, shoe.draw()];
+                let hand2 = vec![second_card, shoe.draw()];
+
+                spot.hands[index] = PlayerHand::new(hand1, bet, true);
+                spot.hands.insert(index + 1, PlayerHand::new(hand2, bet, true));
+            }
+            GameAction::Surrender => {
+                let hand = &mut spot.hands[index];
+                hand.surrendered = true;
+                hand.done = true;
+            }
+        }
+        let cards = self.spots[s].hands[index].cards.clone();
+        let total = hand_value(&cards);
+        self.events.push(TableEvent::Action { spot: s, hand: index, action, cards, total });
+        self.advance();
+        Ok(())
+    }
+
+    fn advance(&mut self) {
+        while let Some(spot) = self.spots.get(self.active.0) {
+            match spot.hands.get(self.active.1) {
+                Some(hand) if !hand.is_finished() => return,
+                Some(_) => self.active.1 += 1,
+                None => self.active = (self.active.0 + 1, 0),
+            }
+        }
+    }
+
+    /// The dealer only draws when some hand at the table is still live.
+    pub fn needs_dealer(&self) -> bool {
+        !self.dealer_has_blackjack()
+            && self
+                .spots
+                .iter()
+                .flat_map(|s| &s.hands)
+                .any(|h| !h.surrendered && hand_value(&h.cards) <= 21)
+    }
+
+    pub fn hole_revealed(&self) -> bool {
+        self.hole_revealed
+    }
+
+    /// Turns the hole card face up so it enters the count.
+    pub fn reveal_hole(&mut self, shoe: &mut Shoe) {
+        if !self.hole_revealed {
+            shoe.reveal(self.dealer[0]);
+            self.hole_revealed = true;
+            let total = hand_value(&self.dealer);
+            self.events.push(TableEvent::Reveal { hole: self.dealer[0], total });
+        }
+    }
+
+    pub fn play_dealer(&mut self, shoe: &mut Shoe) {
+        while self.pending_insurance().is_some() {
+            self.resolve_insurance(shoe, false);
+        }
+        self.reveal_hole(shoe);
+        if self.needs_dealer() {
+            let before = self.dealer.len();
+            dealer_turn(shoe, &mut self.dealer, self.rules.dealer_hits_soft_17);
+            for drawn in before + 1..=self.dealer.len() {
+                let card = self.dealer[drawn - 1];
+                let total = hand_value(&self.dealer[..drawn]);
+                self.events.push(TableEvent::DealerDraw { card, total });
+            }
+        }
+    }
+
+    /// The settled round as an event, for feeds that show the payouts.
+    pub fn settlement(&self, player_edge: f32) -> TableEvent {
+        let spots = (0..self.spots.len())
+            .map(|spot| SpotSettlement { outcomes: self.outcomes(spot, player_edge), net: self.net(spot, player_edge) })
+            .collect();
+        TableEvent::Settlement { dealer: self.dealer.clone(), dealer_total: hand_value(&self.dealer), spots }
+    }
+
+    /// Events since the last call, oldest first.
+    pub fn drain_events(&mut self) -> Vec<TableEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn outcomes(&self, spot: usize, player_edge: f32) -> Vec<HandOutcome> {
+        self.spots[spot]
+            .hands
+            .iter()
+            .map(|hand| {
+                if hand.surrendered {
+                    HandOutcome::Surrender
+                } else {
+                    settle_hand(&hand.cards, hand.is_blackjack(), &self.dealer, player_edge)
+                }
+            })
+            .collect()
+    }
+
+    pub fn total_bet(&self, spot: usize) -> u32 {
+        let spot = &self.spots[spot];
+        spot.hands.iter().map(|h| h.bet).sum::<u32>() + spot.insurance
+    }
+
+    /// Insurance pays 2:1 when the dealer has blackjack.
+    pub fn insurance_net(&self, spot: usize) -> i64 {
+        let stake = i64::from(self.spots[spot].insurance);
+        if self.dealer_has_blackjack() { stake * 2 } else { -stake }
+    }
+
+    pub fn net(&self, spot: usize, player_edge: f32) -> i64 {
+        self.outcomes(spot, player_edge)
+            .iter()
+            .zip(&self.spots[spot].hands)
+            .map(|(outcome, hand)| outcome.payout(hand.bet, self.rules.blackjack_payout))
+            .sum::<i64>()
+            + self.insurance_net(spot)
+    }
+}
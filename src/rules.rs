@@ -0,0 +1,341 @@
+//! Table rules (`[rules]` in game_config.toml) and the named presets that
+//! fill them in. `Rules::house_edge` estimates the house advantage of a rule
+//! set from the usual per-rule effects, so presets can be compared.
+
+use crate::card::{Card, hand_value};
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    pub decks: u8,
+    /// fraction of the shoe dealt before it is reshuffled
+    pub penetration: f32,
+    /// H17: the dealer hits soft 17 (S17 when false)
+    pub dealer_hits_soft_17: bool,
+    pub double: DoubleRule,
+    pub double_after_split: bool,
+    /// most hands one spot may split into
+    pub max_split_hands: u8,
+    pub resplit_aces: bool,
+    /// late surrender of the first two cards
+    pub surrender: bool,
+    pub hole_card: HoleCard,
+    pub blackjack_payout: BlackjackPayout,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            decks: 6,
+            penetration: 0.75,
+            dealer_hits_soft_17: false,
+            double: DoubleRule::Any,
+            double_after_split: true,
+            max_split_hands: 4,
+            resplit_aces: true,
+            surrender: true,
+            hole_card: HoleCard::Peek,
+            blackjack_payout: BlackjackPayout::EvenMoney,
+        }
+    }
+}
+
+/// Which two-card hands may double down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoubleRule {
+    Any,
+    NineToEleven,
+    TenToEleven,
+}
+
+/// `peek`: the dealer checks for blackjack before the players act.
+/// `enhc`: European no-hole-card play; a dealer blackjack is only found after
+/// the players act and takes doubled and split bets too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HoleCard {
+    Peek,
+    Enhc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackjackPayout {
+    ThreeToTwo,
+    SixToFive,
+    EvenMoney,
+}
+
+impl BlackjackPayout {
+    /// Winnings of a natural on `bet`, rounded down.
+    pub fn pay(&self, bet: i64) -> i64 {
+        match self {
+            BlackjackPayout::ThreeToTwo => bet * 3 / 2,
+            BlackjackPayout::SixToFive => bet * 6 / 5,
+            BlackjackPayout::EvenMoney => bet,
+        }
+    }
+
+    pub fn ratio(&self) -> &'static str {
+        match self {
+            BlackjackPayout::ThreeToTwo => "3:2",
+            BlackjackPayout::SixToFive => "6:5",
+            BlackjackPayout::EvenMoney => "1:1",
+        }
+    }
+}
+
+impl Rules {
+    pub fn can_double(&self, cards: &[Card], after_split: bool) -> bool {
+        let total_allowed = match self.double {
+            DoubleRule::Any => true,
+            DoubleRule::NineToEleven => (9..=11).contains(&hand_value(cards)),
+            DoubleRule::TenToEleven => (10..=11).contains(&hand_value(cards)),
+        };
+        total_allowed && (self.double_after_split || !after_split)
+    }
+
+    /// Whether a pair may be split again with `hands` hands already on the spot.
+    pub fn can_split(&self, cards: &[Card], hands: usize, after_split: bool) -> bool {
+        let resplitting_aces = after_split && cards[0].is_ace();
+        hands < usize::from(self.max_split_hands) && (self.resplit_aces || !resplitting_aces)
+    }
+
+    /// Estimated house edge in percent (negative favours the player), from
+    /// 0.43% for eight decks, S17, double any two, DAS, split to four hands,
+    /// no surrender, peek and 3:2, adjusted per rule.
+    pub fn house_edge(&self) -> f64 {
+        let mut edge = 0.43;
+        edge -= match self.decks {
+            1 => 0.48,
+            2 => 0.19,
+            3 => 0.10,
+            4 => 0.06,
+            5 => 0.03,
+            6 => 0.02,
+            7 => 0.01,
+            _ => 0.0,
+        };
+        if self.dealer_hits_soft_17 {
+            edge += 0.22;
+        }
+        edge += match self.double {
+            DoubleRule::Any => 0.0,
+            DoubleRule::NineToEleven => 0.09,
+            DoubleRule::TenToEleven => 0.18,
+        };
+        if !self.double_after_split {
+            edge += 0.14;
+        }
+        edge += match self.max_split_hands {
+            0..=2 => 0.03,
+            3 => 0.01,
+            _ => 0.0,
+        };
+        if self.resplit_aces {
+            edge -= 0.08;
+        }
+        if self.surrender {
+            edge -= 0.08;
+        }
+        if self.hole_card == HoleCard::Enhc {
+            edge += 0.11;
+        }
+        edge += match self.blackjack_payout {
+            BlackjackPayout::ThreeToTwo => 0.0,
+            BlackjackPayout::SixToFive => 1.39,
+            BlackjackPayout::EvenMoney => 2.27,
+        };
+        edge
+    }
+
+    /// One line such as "6 decks / S17 / double any two / ... / BJ 3:2".
+    pub fn summary(&self) -> String {
+        let double = match self.double {
+            DoubleRule::Any => t!("rules.double_any"),
+            DoubleRule::NineToEleven => t!("rules.double_9_11"),
+            DoubleRule::TenToEleven => t!("rules.double_10_11"),
+        };
+        let mut parts = vec![
+            t!("rules.decks", decks = self.decks),
+            if self.dealer_hits_soft_17 { "H17".to_string() } else { "S17".to_string() },
+            double,
+            if self.double_after_split { t!("rules.das") } else { t!("rules.no_das") },
+            t!("rules.split_hands", hands = self.max_split_hands),
+        ];
+        if self.resplit_aces {
+            parts.push(t!("rules.resplit_aces"));
+        }
+        parts.push(if self.surrender { t!("rules.surrender") } else { t!("rules.no_surrender") });
+        parts.push(match self.hole_card {
+            HoleCard::Peek => t!("rules.peek"),
+            HoleCard::Enhc => t!("rules.enhc"),
+        });
+        parts.push(t!("rules.payout", ratio = self.blackjack_payout.ratio()));
+        parts.join(" / ")
+    }
+}
+
+/// `[rules]` as written in the file: only the fields given there override
+/// the preset.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decks: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub penetration: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dealer_hits_soft_17: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub double: Option<DoubleRule>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub double_after_split: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_split_hands: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resplit_aces: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub surrender: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hole_card: Option<HoleCard>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blackjack_payout: Option<BlackjackPayout>,
+}
+
+impl RuleOverrides {
+    pub fn apply(&self, rules: &mut Rules) {
+        if let Some(decks) = self.decks {
+            rules.decks = decks;
+        }
+        if let Some(penetration) = self.penetration {
+            rules.penetration = penetration;
+        }
+        if let Some(h17) = self.dealer_hits_soft_17 {
+            rules.dealer_hits_soft_17 = h17;
+        }
+        if let Some(double) = self.double {
+            rules.double = double;
+        }
+        if let Some(das) = self.double_after_split {
+            rules.double_after_split = das;
+        }
+        if let Some(hands) = self.max_split_hands {
+            rules.max_split_hands = hands;
+        }
+        if let Some(rsa) = self.resplit_aces {
+            rules.resplit_aces = rsa;
+        }
+        if let Some(surrender) = self.surrender {
+            rules.surrender = surrender;
+        }
+        if let Some(hole_card) = self.hole_card {
+            rules.hole_card = hole_card;
+        }
+        if let Some(payout) = self.blackjack_payout {
+            rules.blackjack_payout = payout;
+        }
+    }
+}
+
+/// Named rule sets, typical of the casinos they are named after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    LasVegasStrip,
+    Downtown,
+    AtlanticCity,
+    European,
+    Macau,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] =
+        [Preset::LasVegasStrip, Preset::Downtown, Preset::AtlanticCity, Preset::European, Preset::Macau];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Preset::LasVegasStrip => "las_vegas_strip",
+            Preset::Downtown => "downtown",
+            Preset::AtlanticCity => "atlantic_city",
+            Preset::European => "european",
+            Preset::Macau => "macau",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.key() == key)
+    }
+
+    pub fn label(&self) -> String {
+        t!(&format!("preset.{}", self.key()))
+    }
+
+    pub fn rules(&self) -> Rules {
+        let base = Rules {
+            decks: 6,
+            penetration: 0.75,
+            dealer_hits_soft_17: false,
+            double: DoubleRule::Any,
+            double_after_split: true,
+            max_split_hands: 4,
+            resplit_aces: false,
+            surrender: false,
+            hole_card: HoleCard::Peek,
+            blackjack_payout: BlackjackPayout::ThreeToTwo,
+        };
+        match self {
+            Preset::LasVegasStrip => Rules { surrender: true, ..base },
+            Preset::Downtown => Rules { decks: 2, penetration: 0.65, dealer_hits_soft_17: true, ..base },
+            Preset::AtlanticCity => Rules { decks: 8, penetration: 0.8, surrender: true, ..base },
+            Preset::European => Rules {
+                double: DoubleRule::NineToEleven,
+                max_split_hands: 2,
+                hole_card: HoleCard::Enhc,
+                ..base
+            },
+            Preset::Macau => Rules { decks: 8, max_split_hands: 2, surrender: true, hole_card: HoleCard::Enhc, ..base },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_edge(rules: Rules, expected: f64) {
+        let edge = rules.house_edge();
+        assert!((edge - expected).abs() < 1e-9, "{} != {}", edge, expected);
+    }
+
+    #[test]
+    fn preset_house_edges() {
+        assert_edge(Preset::LasVegasStrip.rules(), 0.33);
+        assert_edge(Preset::Downtown.rules(), 0.46);
+        assert_edge(Preset::AtlanticCity.rules(), 0.35);
+        assert_edge(Preset::European.rules(), 0.64);
+        assert_edge(Preset::Macau.rules(), 0.49);
+    }
+
+    #[test]
+    fn default_rules_pay_even_money_on_blackjack() {
+        assert_edge(Rules::default(), 2.52);
+    }
+
+    #[test]
+    fn each_rule_moves_the_edge() {
+        let base = Preset::LasVegasStrip.rules();
+        let moved = |rules: Rules| rules.house_edge() - base.house_edge();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert!(close(moved(Rules { decks: 1, ..base }), 0.02 - 0.48));
+        assert!(close(moved(Rules { dealer_hits_soft_17: true, ..base }), 0.22));
+        assert!(close(moved(Rules { double: DoubleRule::TenToEleven, ..base }), 0.18));
+        assert!(close(moved(Rules { double_after_split: false, ..base }), 0.14));
+        assert!(close(moved(Rules { resplit_aces: true, ..base }), -0.08));
+        assert!(close(moved(Rules { surrender: false, ..base }), 0.08));
+        assert!(close(moved(Rules { hole_card: HoleCard::Enhc, ..base }), 0.11));
+        assert!(close(moved(Rules { blackjack_payout: BlackjackPayout::SixToFive, ..base }), 1.39));
+    }
+}
@@ -0,0 +1,575 @@
+use crate::card::hand_value;
+use crate::config::GameConfig;
+use crate::events::{EventSink, TableEvent};
+use crate::game_action::GameAction;
+use crate::player::PlayerState;
+use crate::protocol::{ClientMessage, RoundResultView, SeatResult, ServerMessage, TableView, send};
+use crate::round::{Round, Wager};
+use crate::shoe::Shoe;
+use crate::t;
+use crate::table::MAX_SEATS;
+use std::io::{BufRead, BufReader};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct ServeOptions {
+    pub addr: String,
+    /// time a seat has to answer before it is auto-stood (or bet / declined)
+    pub timeout: Duration,
+    /// pause between rounds so new players can sit down
+    pub round_gap: Duration,
+}
+
+/// What the connection threads report to the table.
+enum Inbound {
+    Joined { id: usize, name: String, writer: TcpStream },
+    Message { id: usize, message: ClientMessage },
+    Invalid { id: usize, error: String },
+    Left { id: usize },
+}
+
+struct Client {
+    id: usize,
+    /// seat number from `welcome`, kept until the client leaves the table
+    seat: usize,
+    name: String,
+    writer: TcpStream,
+    player: PlayerState,
+    connected: bool,
+}
+
+/// Hosts a table: owns the shoe and the rule engine and drives every seat
+/// through the line-delimited JSON protocol. Every table event is also
+/// published to `sinks`.
+pub fn run_server(cfg: &GameConfig, opts: &ServeOptions, sinks: Vec<Box<dyn EventSink>>) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(&opts.addr)?;
+    println!("{}", t!("server.opened", addr = listener.local_addr()?, timeout = opts.timeout.as_secs()));
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || accept_loop(listener, tx));
+
+    let mut table = NetTable {
+        cfg,
+        opts,
+        shoe: Shoe::new(cfg.rules.decks, cfg.rules.penetration, rand::random()),
+        clients: Vec::new(),
+        rx,
+        sinks,
+    };
+    loop {
+        table.wait_for_players();
+        table.play_round()?;
+        table.pause(opts.round_gap);
+    }
+}
+
+fn accept_loop(listener: TcpListener, tx: Sender<Inbound>) {
+    for (id, stream) in listener.incoming().flatten().enumerate() {
+        let tx = tx.clone();
+        thread::spawn(move || read_client(id, stream, tx));
+    }
+}
+
+/// Forwards one connection's lines to the table until it disconnects.
+fn read_client(id: usize, stream: TcpStream, tx: Sender<Inbound>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut joined = false;
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let inbound = match serde_json::from_str::<ClientMessage>(&line) {
+            Ok(ClientMessage::Hello { name }) if !joined => {
+                joined = true;
+                let Ok(writer) = writer.try_clone() else {
+                    break;
+                };
+                Inbound::Joined { id, name, writer }
+            }
+            // nothing is seated yet, so the table cannot answer for us
+            Ok(_) | Err(_) if !joined => {
+                let message = t!("server.hello_first");
+                if send(&mut writer, &ServerMessage::Error { message }).is_err() {
+                    break;
+                }
+                continue;
+            }
+            Ok(message) => Inbound::Message { id, message },
+            Err(e) => Inbound::Invalid { id, error: e.to_string() },
+        };
+        if tx.send(inbound).is_err() {
+            return;
+        }
+    }
+    if joined {
+        let _ = tx.send(Inbound::Left { id });
+    }
+}
+
+struct NetTable<'a> {
+    cfg: &'a GameConfig,
+    opts: &'a ServeOptions,
+    shoe: Shoe,
+    clients: Vec<Client>,
+    rx: Receiver<Inbound>,
+    sinks: Vec<Box<dyn EventSink>>,
+}
+
+impl NetTable<'_> {
+    fn send_to(&mut self, index: usize, message: &ServerMessage) {
+        let client = &mut self.clients[index];
+        if client.connected && send(&mut client.writer, message).is_err() {
+            client.connected = false;
+        }
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for index in 0..self.clients.len() {
+            self.send_to(index, message);
+        }
+    }
+
+    fn announce(&mut self, text: String) {
+        println!("{}", text);
+        self.broadcast(&ServerMessage::Event { text });
+    }
+
+    fn publish(&mut self, event: &TableEvent) {
+        for sink in &mut self.sinks {
+            sink.publish(event);
+        }
+    }
+
+    /// Passes on what the round engine recorded since the last call.
+    fn publish_round(&mut self, round: &mut Round) {
+        for event in round.drain_events() {
+            self.publish(&event);
+        }
+    }
+
+    fn index_of(&self, id: usize) -> Option<usize> {
+        self.clients.iter().position(|c| c.id == id)
+    }
+
+    /// Next message from a seated client before `deadline`. Joins and
+    /// malformed lines are handled on the way; a `None` message means the
+    /// client left.
+    fn next_message(&mut self, deadline: Instant) -> Option<(usize, Option<ClientMessage>)> {
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let inbound = match self.rx.recv_timeout(remaining) {
+                Ok(inbound) => inbound,
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return None,
+            };
+            match inbound {
+                Inbound::Joined { id, name, writer } => self.seat(id, name, writer),
+                Inbound::Invalid { id, error } => {
+                    if let Some(index) = self.index_of(id) {
+                        let message = t!("error.bad_message", error = error);
+                        self.send_to(index, &ServerMessage::Error { message });
+                    }
+                }
+                Inbound::Message { id, message: ClientMessage::Leave } | Inbound::Left { id } => {
+                    // turned away at the door (table full)
+                    let Some(index) = self.index_of(id) else {
+                        continue;
+                    };
+                    if self.clients[index].connected {
+                        self.clients[index].connected = false;
+                        let _ = self.clients[index].writer.shutdown(Shutdown::Both);
+                        let name = self.clients[index].name.clone();
+                        self.announce(t!("server.left", name = name));
+                    }
+                    return Some((index, None));
+                }
+                Inbound::Message { id, message } => {
+                    if let Some(index) = self.index_of(id) {
+                        return Some((index, Some(message)));
+                    }
+                }
+            }
+        }
+    }
+
+    fn seat(&mut self, id: usize, name: String, mut writer: TcpStream) {
+        // a seat left during a round stays taken until the round is over
+        let Some(seat) = (0..MAX_SEATS).find(|&seat| self.clients.iter().all(|c| c.seat != seat)) else {
+            let message = t!("server.full", seats = MAX_SEATS);
+            let _ = send(&mut writer, &ServerMessage::Error { message });
+            let _ = writer.shutdown(Shutdown::Both);
+            return;
+        };
+        let player = PlayerState::with_system(self.cfg, self.cfg.betting_system);
+        let welcome = ServerMessage::Welcome {
+            seat,
+            bank: player.bank,
+            currency: self.cfg.currency_name.clone(),
+            timeout_secs: self.opts.timeout.as_secs(),
+        };
+        self.clients.push(Client { id, seat, name: name.clone(), writer, player, connected: true });
+        self.send_to(self.clients.len() - 1, &welcome);
+        self.announce(t!("server.joined", name = name));
+    }
+
+    /// Rejects anything a client sends while no answer is expected of it.
+    fn not_now(&mut self, index: usize) {
+        let message = t!("server.not_now");
+        self.send_to(index, &ServerMessage::Error { message });
+    }
+
+    fn pause(&mut self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while let Some((index, message)) = self.next_message(deadline) {
+            if message.is_some() {
+                self.not_now(index);
+            }
+        }
+    }
+
+    /// Blocks until someone connected has money to play.
+    fn wait_for_players(&mut self) {
+        self.clients.retain(|c| c.connected);
+        if !self.clients.iter().any(|c| c.player.can_play(self.cfg)) {
+            println!("{}", t!("server.waiting"));
+        }
+        while !self.clients.iter().any(|c| c.player.can_play(self.cfg)) {
+            // joins are absorbed by next_message, so poll instead of blocking
+            self.pause(Duration::from_millis(200));
+            self.clients.retain(|c| c.connected);
+        }
+    }
+
+    /// Asks every player for a bet at once; silence bets the suggested amount.
+    fn collect_bets(&mut self, players: &[usize]) -> Vec<Option<u32>> {
+        let cfg = self.cfg;
+        for &index in players {
+            let player = &self.clients[index].player;
+            let request = ServerMessage::BetRequest {
+                min: cfg.table_min_bet,
+                max: cfg.table_max_bet.min(player.bank),
+                suggested: player.bet,
+            };
+            self.send_to(index, &request);
+        }
+
+        let mut bets: Vec<Option<u32>> = vec![None; players.len()];
+        let deadline = Instant::now() + self.opts.timeout;
+        let mut waiting = players.len();
+        while waiting > 0 {
+            let Some((index, message)) = self.next_message(deadline) else {
+                break;
+            };
+            let Some(slot) = players.iter().position(|&p| p == index).filter(|&s| bets[s].is_none()) else {
+                if message.is_some() {
+                    self.not_now(index);
+                }
+                continue;
+            };
+            match message {
+                None => waiting -= 1,
+                Some(ClientMessage::Bet { amount }) => {
+                    let bank = self.clients[index].player.bank;
+                    if amount < cfg.table_min_bet || amount > cfg.table_max_bet.min(bank) {
+                        let message = t!(
+                            "error.bet_range",
+                            min = cfg.table_min_bet,
+                            max = cfg.table_max_bet.min(bank),
+                            currency = cfg.currency_name
+                        );
+                        self.send_to(index, &ServerMessage::Error { message });
+                        continue;
+                    }
+                    self.clients[index].player.bet = amount;
+                    bets[slot] = Some(amount);
+                    waiting -= 1;
+                }
+                Some(_) => self.not_now(index),
+            }
+        }
+
+        for (slot, &index) in players.iter().enumerate() {
+            let client = &self.clients[index];
+            if bets[slot].is_none() && client.connected {
+                bets[slot] = Some(client.player.bet);
+                let text =
+                    t!("server.timeout_bet", name = client.name, bet = client.player.bet, currency = cfg.currency_name);
+                self.announce(text);
+            }
+        }
+        bets
+    }
+
+    /// Waits for the acting seat's answer; `None` on timeout or disconnect.
+    fn ask<T>(
+        &mut self,
+        index: usize,
+        request: &ServerMessage,
+        mut accept: impl FnMut(ClientMessage) -> Result<T, String>,
+    ) -> Option<T> {
+        self.send_to(index, request);
+        let deadline = Instant::now() + self.opts.timeout;
+        while self.clients[index].connected {
+            let (from, message) = self.next_message(deadline)?;
+            match message {
+                Some(message) if from == index => match accept(message) {
+                    Ok(answer) => return Some(answer),
+                    Err(message) => self.send_to(index, &ServerMessage::Error { message }),
+                },
+                Some(_) => self.not_now(from),
+                None => {}
+            }
+        }
+        None
+    }
+
+    fn send_state(&mut self, round: &Round, playing: &[usize]) {
+        let seats: Vec<(usize, &str)> =
+            playing.iter().map(|&i| (self.clients[i].seat, self.clients[i].name.as_str())).collect();
+        let view = TableView::new(round, &seats, self.shoe.remaining());
+        self.broadcast(&ServerMessage::State(view));
+    }
+
+    fn play_round(&mut self) -> anyhow::Result<()> {
+        let cfg = self.cfg;
+        if self.shoe.needs_shuffle() {
+            self.shoe.shuffle();
+            self.announce(t!("game.shuffled", decks = cfg.rules.decks));
+            self.publish(&TableEvent::Shuffle { decks: cfg.rules.decks });
+        }
+
+        let players: Vec<usize> = (0..self.clients.len())
+            .filter(|&i| self.clients[i].connected && self.clients[i].player.can_play(cfg))
+            .collect();
+        let bets = self.collect_bets(&players);
+        let (playing, wagers): (Vec<usize>, Vec<Wager>) = players
+            .iter()
+            .zip(&bets)
+            .filter(|&(&i, _)| self.clients[i].connected)
+            .filter_map(|(&i, bet)| bet.map(|bet| (i, Wager { bet, bankroll: self.clients[i].player.bank })))
+            .unzip();
+        if playing.is_empty() {
+            return Ok(());
+        }
+
+        let mut round = Round::deal(&mut self.shoe, &wagers, &cfg.rules);
+        let seats = playing.iter().map(|&i| self.clients[i].name.clone()).collect();
+        self.publish(&TableEvent::Table { seats });
+        self.publish_round(&mut round);
+        self.send_state(&round, &playing);
+
+        while let Some(spot) = round.pending_insurance() {
+            let index = playing[spot];
+            let cost = round.spots[spot].hands[0].bet / 2;
+            let take = self
+                .ask(index, &ServerMessage::InsuranceRequest { cost }, |m| match m {
+                    ClientMessage::Insurance { take } => Ok(take),
+                    _ => Err(t!("server.expected", kind = "insurance")),
+                })
+                .unwrap_or(false);
+            round.resolve_insurance(&mut self.shoe, take);
+            self.publish_round(&mut round);
+            let name = self.clients[index].name.clone();
+            let key = if take { "server.insured" } else { "server.declined_insurance" };
+            self.announce(t!(key, name = name));
+        }
+        if round.hole_revealed() && round.dealer_has_blackjack() {
+            self.announce(t!("game.dealer_blackjack"));
+        }
+
+        while let Some((spot, hand)) = round.active() {
+            let index = playing[spot];
+            let legal = round.legal_actions();
+            let cards = round.spots[spot].hands[hand].cards.clone();
+            let request = ServerMessage::ActionRequest { hand, value: hand_value(&cards), cards, legal: legal.clone() };
+            let answer = self.ask(index, &request, |m| match m {
+                ClientMessage::Action { action } if legal.contains(&action) => Ok(action),
+                ClientMessage::Action { action } => Err(t!("error.illegal_action", action = action.name())),
+                _ => Err(t!("server.expected", kind = "action")),
+            });
+            let name = self.clients[index].name.clone();
+            let action = answer.unwrap_or(GameAction::Stand);
+            round.apply(&mut self.shoe, action)?;
+            self.publish_round(&mut round);
+
+            let cards = &round.spots[spot].hands[hand].cards;
+            let text = match action {
+                GameAction::Hit | GameAction::DoubleDown => t!(
+                    "server.draw",
+                    name = name,
+                    action = action.name(),
+                    card = cards[cards.len() - 1].short(),
+                    total = hand_value(cards)
+                ),
+                GameAction::Stand if answer.is_none() => t!("server.timeout_stand", name = name, total = hand_value(cards)),
+                GameAction::Stand => t!("server.stand", name = name, total = hand_value(cards)),
+                GameAction::Split | GameAction::Surrender => t!("server.action", name = name, action = action.name()),
+            };
+            self.announce(text);
+            self.send_state(&round, &playing);
+        }
+
+        round.play_dealer(&mut self.shoe);
+        self.publish_round(&mut round);
+        self.publish(&round.settlement(cfg.player_edge));
+        self.send_state(&round, &playing);
+
+        let mut seats = Vec::with_capacity(playing.len());
+        for (spot, &index) in playing.iter().enumerate() {
+            let net = round.net(spot, cfg.player_edge);
+            let client = &mut self.clients[index];
+            client.player.settle(net, cfg);
+            seats.push(SeatResult {
+                seat: client.seat,
+                name: client.name.clone(),
+                outcomes: round.outcomes(spot, cfg.player_edge),
+                net,
+                bank: client.player.bank,
+            });
+        }
+        for seat in &seats {
+            println!(
+                "{}",
+                t!(
+                    "server.seat_result",
+                    name = seat.name,
+                    net = format!("{:+}", seat.net),
+                    bank = seat.bank,
+                    currency = cfg.currency_name
+                )
+            );
+        }
+        let result = RoundResultView { dealer: round.dealer.clone(), dealer_value: hand_value(&round.dealer), seats };
+        self.broadcast(&ServerMessage::Result(result));
+
+        for index in playing {
+            if !self.clients[index].player.can_play(cfg) {
+                let name = self.clients[index].name.clone();
+                self.announce(t!("server.out_of_money", name = name));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+    use std::io::Lines;
+
+    /// A table on a free local port with short timeouts; it runs until the tests end.
+    fn open_table() -> String {
+        let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let opts = ServeOptions {
+            addr: addr.clone(),
+            timeout: Duration::from_millis(200),
+            round_gap: Duration::from_millis(50),
+        };
+        thread::spawn(move || run_server(&GameConfig::default(), &opts, Vec::new()));
+        for _ in 0..100 {
+            if TcpStream::connect(&addr).is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        addr
+    }
+
+    struct TestClient {
+        writer: TcpStream,
+        lines: Lines<BufReader<TcpStream>>,
+    }
+
+    impl TestClient {
+        fn connect(addr: &str) -> Self {
+            let stream = TcpStream::connect(addr).unwrap();
+            stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+            Self { writer: stream.try_clone().unwrap(), lines: BufReader::new(stream).lines() }
+        }
+
+        fn send(&mut self, message: &ClientMessage) {
+            send(&mut self.writer, message).unwrap();
+        }
+
+        fn next(&mut self) -> ServerMessage {
+            serde_json::from_str(&self.lines.next().unwrap().unwrap()).unwrap()
+        }
+
+        /// Sends `hello` and returns the seat number from `welcome`.
+        fn join(&mut self, name: &str) -> usize {
+            self.send(&ClientMessage::Hello { name: name.to_string() });
+            loop {
+                if let ServerMessage::Welcome { seat, .. } = self.next() {
+                    return seat;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hello_comes_before_anything_else() {
+        let addr = open_table();
+        let mut client = TestClient::connect(&addr);
+        client.send(&ClientMessage::Bet { amount: 10 });
+        match client.next() {
+            ServerMessage::Error { message } => assert_eq!(message, t!("server.hello_first")),
+            other => panic!("expected an error, got {:?}", other),
+        }
+        assert_eq!(client.join("alice"), 0);
+    }
+
+    #[test]
+    fn a_silent_turn_is_stood() {
+        let addr = open_table();
+        let mut client = TestClient::connect(&addr);
+        client.join("alice");
+        // a natural on either side skips the turn, so play rounds until one is asked for
+        let mut asked: Option<Vec<Card>> = None;
+        for _ in 0..200 {
+            match client.next() {
+                ServerMessage::ActionRequest { cards, .. } => asked = Some(cards),
+                ServerMessage::Event { text } if asked.is_some() => {
+                    let cards = asked.as_ref().unwrap();
+                    assert_eq!(text, t!("server.timeout_stand", name = "alice", total = hand_value(cards)));
+                    let ServerMessage::State(view) = client.next() else {
+                        panic!("expected the table after the turn");
+                    };
+                    let hand = &view.seats[0].hands[0];
+                    assert_eq!(serde_json::to_value(&hand.cards).unwrap(), serde_json::to_value(cards).unwrap());
+                    assert!(!hand.doubled && !hand.surrendered);
+                    return;
+                }
+                _ => {}
+            }
+        }
+        panic!("never asked for an action");
+    }
+
+    #[test]
+    fn seat_numbers_survive_a_departure() {
+        let addr = open_table();
+        let mut first = TestClient::connect(&addr);
+        let mut second = TestClient::connect(&addr);
+        assert_eq!(first.join("alice"), 0);
+        assert_eq!(second.join("bob"), 1);
+        first.send(&ClientMessage::Leave);
+        let mut third = TestClient::connect(&addr);
+        assert_ne!(third.join("carol"), 1);
+        for _ in 0..200 {
+            // the first round may have been dealt before bob sat down
+            if let ServerMessage::State(view) = second.next()
+                && let Some(bob) = view.seats.iter().find(|s| s.name == "bob")
+            {
+                assert_eq!(bob.seat, 1);
+                return;
+            }
+        }
+        panic!("no table state reached the second seat");
+    }
+}
@@ -0,0 +1,391 @@
+//! A sitting at the terminal table, from the first round until the player
+//! leaves: the settings, each seat's bankroll, the shoe and the statistics,
+//! with the round loop that plays them. The session is saved after every
+//! action, a round in progress included, and offered again on the next
+//! launch, so closing the terminal on a bad hand does not undo it.
+
+use crate::bots::Controller;
+use crate::config::GameConfig;
+use crate::game::{PlayedRound, humans_broke, play_round, post_game_menu};
+use crate::player::SavedPlayer;
+use crate::prompt;
+use crate::round::Round;
+use crate::shoe::Shoe;
+use crate::t;
+use crate::table::{Seat, Table};
+use serde::{Deserialize, Serialize};
+use dialoguer::console::Term;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Mutex;
+
+/// Held while the session file is written, so Ctrl+C never exits halfway.
+static WRITING: Mutex<()> = Mutex::new(());
+
+/// Results of the human seats over the session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub rounds: u32,
+    pub hands: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub net: i64,
+    /// best and worst single-round result
+    pub best: i64,
+    pub worst: i64,
+}
+
+impl SessionStats {
+    fn record(&mut self, cfg: &GameConfig, table: &Table, played: &PlayedRound) {
+        self.rounds += 1;
+        for (spot, &seat) in played.seats.iter().enumerate() {
+            if !table.seats[seat].controller.is_human() {
+                continue;
+            }
+            let hands = &played.round.spots[spot].hands;
+            for (hand, outcome) in hands.iter().zip(played.round.outcomes(spot, cfg.player_edge)) {
+                self.hands += 1;
+                match outcome.payout(hand.bet, cfg.rules.blackjack_payout) {
+                    n if n > 0 => self.wins += 1,
+                    n if n < 0 => self.losses += 1,
+                    _ => self.pushes += 1,
+                }
+                if hand.is_blackjack() {
+                    self.blackjacks += 1;
+                }
+            }
+            let net = played.round.net(spot, cfg.player_edge);
+            self.net += net;
+            self.best = self.best.max(net);
+            self.worst = self.worst.min(net);
+        }
+    }
+
+    pub fn print(&self, cfg: &GameConfig) {
+        if self.rounds == 0 {
+            return;
+        }
+        println!(
+            "\n{}",
+            t!(
+                "session.stats",
+                rounds = self.rounds,
+                hands = self.hands,
+                wins = self.wins,
+                losses = self.losses,
+                pushes = self.pushes,
+                blackjacks = self.blackjacks,
+                net = format!("{:+}", self.net),
+                best = format!("{:+}", self.best),
+                worst = format!("{:+}", self.worst),
+                currency = cfg.currency_name
+            )
+        );
+    }
+}
+
+pub struct Session {
+    pub cfg: GameConfig,
+    pub table: Table,
+    pub shoe: Shoe,
+    pub stats: SessionStats,
+    /// the round in progress as of its last action
+    round: Option<PlayedRound>,
+    /// where the session is kept between launches; `None` plays unsaved
+    path: Option<PathBuf>,
+}
+
+/// The session as written to disk.
+#[derive(Serialize, Deserialize)]
+struct SavedSession {
+    cfg: GameConfig,
+    seats: Vec<SavedSeat>,
+    shoe: Shoe,
+    stats: SessionStats,
+    #[serde(default)]
+    round: Option<PlayedRound>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SavedSeat {
+    name: String,
+    controller: Controller,
+    #[serde(flatten)]
+    player: SavedPlayer,
+}
+
+impl Session {
+    /// A fresh table, played unsaved unless given a path with `saved_to`.
+    pub fn new(cfg: GameConfig, seed: u64) -> Self {
+        let table = Table::new(&cfg);
+        let shoe = Shoe::new(cfg.rules.decks, cfg.rules.penetration, seed);
+        Self { cfg, table, shoe, stats: SessionStats::default(), round: None, path: None }
+    }
+
+    /// A fresh table saved to `path`.
+    pub fn saved_to(self, path: PathBuf) -> Self {
+        Self { path: Some(path), ..self }
+    }
+
+    /// The session saved at `path`, if there is one. Game settings come from
+    /// the save; the display settings (language, cards, controls,
+    /// accessibility) and the deviations follow this launch's `cfg`.
+    pub fn load(path: PathBuf, cfg: &GameConfig) -> anyhow::Result<Option<Self>> {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let saved: SavedSession = serde_json::from_str(&text)?;
+        let mut session_cfg = GameConfig {
+            lang: cfg.lang.clone(),
+            card_style: cfg.card_style,
+            input_mode: cfg.input_mode,
+            accessible: cfg.accessible,
+            deviations: cfg.deviations.clone(),
+            file: cfg.file.clone(),
+            ..saved.cfg
+        };
+        session_cfg.apply_rules();
+        let seats = saved
+            .seats
+            .into_iter()
+            .map(|s| Seat::resume(&session_cfg, s.name, s.controller, &s.player))
+            .collect();
+        let table = Table { seats };
+        if let Some(played) = &saved.round
+            && (played.seats.len() != played.round.spots.len() || played.seats.iter().any(|&i| i >= table.seats.len()))
+        {
+            anyhow::bail!(t!("session.round_mismatch"));
+        }
+        Ok(Some(Self {
+            cfg: session_cfg,
+            table,
+            shoe: saved.shoe,
+            stats: saved.stats,
+            round: saved.round,
+            path: Some(path),
+        }))
+    }
+
+    /// Plays rounds until the player leaves the table or nobody can bet,
+    /// starting with the round in progress if there is one. An interruption
+    /// (Ctrl+C) saves the session before it is passed on, with the round it
+    /// cut short to be finished later.
+    pub fn run(&mut self) -> anyhow::Result<()> {
+        self.save()?;
+        let result = self.round_loop();
+        if let Err(e) = &result
+            && prompt::is_interrupted(e)
+            && self.path.is_some()
+        {
+            match self.save() {
+                Ok(()) => println!("\n{}", t!("session.saved_on_interrupt")),
+                Err(save_error) => eprintln!("{}", t!("session.save_failed", error = save_error)),
+            }
+        }
+        result
+    }
+
+    fn round_loop(&mut self) -> anyhow::Result<()> {
+        loop {
+            // the bankrolls only change when the round is settled
+            let seats = self.saved_seats();
+            let resume = self.round.clone();
+            let (cfg, stats, path, in_play) = (&self.cfg, &self.stats, &self.path, &mut self.round);
+            let mut checkpoint = |playing: &[usize], round: &Round, shoe: &Shoe| {
+                let played = PlayedRound { round: round.clone(), seats: playing.to_vec() };
+                *in_play = Some(played.clone());
+                let Some(path) = path else {
+                    return Ok(());
+                };
+                let saved = SavedSession {
+                    cfg: cfg.clone(),
+                    seats: seats.clone(),
+                    shoe: shoe.clone(),
+                    stats: stats.clone(),
+                    round: Some(played),
+                };
+                write(path, &saved)
+            };
+            let played = play_round(&self.cfg, &mut self.table, &mut self.shoe, resume, &mut checkpoint)?;
+            self.round = None;
+            let Some(played) = played else {
+                self.stats.print(&self.cfg);
+                return self.save();
+            };
+            self.stats.record(&self.cfg, &self.table, &played);
+            self.save()?;
+            if !post_game_menu(&mut self.cfg, &mut self.table, &mut self.shoe)? {
+                self.stats.print(&self.cfg);
+                return self.save();
+            }
+            // a new bet or setting is kept even if the next deal never comes
+            self.save()?;
+        }
+    }
+
+    /// Writes the session where the next launch finds it. A session nobody
+    /// can continue, every human seat being broke, is removed instead.
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if humans_broke(&self.cfg, &self.table) {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+        let saved = SavedSession {
+            cfg: self.cfg.clone(),
+            seats: self.saved_seats(),
+            shoe: self.shoe.clone(),
+            stats: self.stats.clone(),
+            round: self.round.clone(),
+        };
+        write(path, &saved)
+    }
+
+    /// Ctrl+C outside a prompt (while the dealer or the bots play, or at an
+    /// `--accessible` line prompt) ends the program. The session on disk is
+    /// current after every action, so only a write in progress is waited
+    /// for. The arrow-key prompts read Ctrl+C as a key and are interrupted
+    /// where they are asked instead.
+    pub fn exit_on_interrupt(&self) -> anyhow::Result<()> {
+        if self.path.is_none() {
+            return Ok(());
+        }
+        ctrlc::set_handler(|| {
+            let _writing = WRITING.lock();
+            let term = Term::stdout();
+            if term.is_term() {
+                let _ = term.show_cursor();
+            }
+            println!("\n{}", t!("session.saved_on_interrupt"));
+            process::exit(130);
+        })?;
+        Ok(())
+    }
+
+    /// Whether a round was left unfinished; it is played before anything
+    /// else that would touch the shoe.
+    pub fn in_round(&self) -> bool {
+        self.round.is_some()
+    }
+
+    fn saved_seats(&self) -> Vec<SavedSeat> {
+        self.table
+            .seats
+            .iter()
+            .map(|s| SavedSeat { name: s.name.clone(), controller: s.controller, player: s.player.saved() })
+            .collect()
+    }
+
+    /// One line about the saved session for the resume question.
+    fn describe(&self) -> String {
+        let banks: Vec<String> = self
+            .table
+            .seats
+            .iter()
+            .filter(|s| s.controller.is_human())
+            .map(|s| t!("session.seat_bank", name = s.name, bank = s.player.bank, currency = self.cfg.currency_name))
+            .collect();
+        let key = if self.in_round() { "session.summary_mid_round" } else { "session.summary" };
+        t!(key, rounds = self.stats.rounds, banks = banks.join(", "))
+    }
+}
+
+fn write(path: &Path, saved: &SavedSession) -> anyhow::Result<()> {
+    let _writing = WRITING.lock();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // write and rename, so a crash mid-write leaves the previous save
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_string(saved)?)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// `$XDG_STATE_HOME/veintiuna/session.json`, else under `~/.local/state`
+/// (`%LOCALAPPDATA%` on Windows).
+pub fn session_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(base.join("veintiuna").join("session.json"))
+}
+
+/// The session to play, from the menu or `--direct`: the saved one if the player wants to
+/// resume it (`true` with it), else a new one. A save with a round in
+/// progress is always resumed. A save that cannot be read is reported and
+/// replaced.
+pub fn open(cfg: &GameConfig, seed: u64) -> anyhow::Result<(Session, bool)> {
+    let fresh = Session::new(cfg.clone(), seed);
+    let Some(path) = session_path() else {
+        return Ok((fresh, false));
+    };
+    let saved = match Session::load(path.clone(), cfg) {
+        Ok(saved) => saved,
+        Err(e) => {
+            eprintln!("{}", t!("session.load_failed", path = path.display(), error = e));
+            None
+        }
+    };
+    if let Some(saved) = saved {
+        // an unfinished round is played out first; starting over would undo it
+        if saved.in_round() {
+            println!("{}", t!("session.round_pending", summary = saved.describe()));
+            return Ok((saved, true));
+        }
+        let question = t!("session.resume_prompt", summary = saved.describe());
+        if prompt::select(&question, &[t!("session.resume"), t!("session.new")], 0)? == 0 {
+            println!("{}", t!("session.resumed"));
+            return Ok((saved, true));
+        }
+    }
+    Ok((fresh.saved_to(path), false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::round::Wager;
+
+    #[test]
+    fn a_saved_round_resumes_where_it_stopped() {
+        let path = env::temp_dir().join(format!("veintiuna-session-{}.json", process::id()));
+        let cfg = GameConfig::default();
+        let mut session = Session::new(cfg.clone(), 7).saved_to(path.clone());
+        let wager = Wager { bet: cfg.bet_amount, bankroll: cfg.player_starting_bank };
+        let round = Round::deal(&mut session.shoe, &[wager], &session.cfg.rules);
+        session.round = Some(PlayedRound { round, seats: vec![0] });
+        session.save().unwrap();
+
+        let mut loaded = Session::load(path.clone(), &cfg).unwrap().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.in_round());
+        let banks = |s: &Session| s.table.seats.iter().map(|seat| (seat.player.bank, seat.player.bet)).collect::<Vec<_>>();
+        assert_eq!(banks(&session), banks(&loaded));
+        let (before, after) = (session.round.as_mut().unwrap(), loaded.round.as_mut().unwrap());
+        assert_eq!(serde_json::to_value(&before.round).unwrap(), serde_json::to_value(&after.round).unwrap());
+        assert_eq!(before.seats, after.seats);
+        assert_eq!(before.round.active(), after.round.active());
+        assert_eq!(session.shoe.remaining(), loaded.shoe.remaining());
+
+        // the same action draws the same card from either shoe
+        let action = before.round.legal_actions()[0];
+        before.round.apply(&mut session.shoe, action).unwrap();
+        after.round.apply(&mut loaded.shoe, action).unwrap();
+        assert_eq!(serde_json::to_value(&before.round).unwrap(), serde_json::to_value(&after.round).unwrap());
+        let (next, next_loaded) = (session.shoe.draw(), loaded.shoe.draw());
+        assert_eq!((next.0, next.1), (next_loaded.0, next_loaded.1));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::headless::HeadlessSession;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Where the REST API keeps its sessions between requests.
+pub trait SessionStore {
+    /// Checks a session out for one request; `put` returns it.
+    fn take(&mut self, id: &str) -> anyhow::Result<Option<HeadlessSession>>;
+    fn put(&mut self, id: &str, session: HeadlessSession) -> anyhow::Result<()>;
+    fn remove(&mut self, id: &str) -> anyhow::Result<bool>;
+}
+
+/// Sessions live as long as the server process.
+#[derive(Default)]
+pub struct MemoryStore {
+    sessions: HashMap<String, HeadlessSession>,
+}
+
+impl SessionStore for MemoryStore {
+    fn take(&mut self, id: &str) -> anyhow::Result<Option<HeadlessSession>> {
+        Ok(self.sessions.remove(id))
+    }
+
+    fn put(&mut self, id: &str, session: HeadlessSession) -> anyhow::Result<()> {
+        self.sessions.insert(id.to_string(), session);
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> anyhow::Result<bool> {
+        Ok(self.sessions.remove(id).is_some())
+    }
+}
+
+/// One JSON file per session, so games survive a server restart.
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(dir: PathBuf) -> anyhow::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+impl SessionStore for FileStore {
+    fn take(&mut self, id: &str) -> anyhow::Result<Option<HeadlessSession>> {
+        let path = self.path(id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    fn put(&mut self, id: &str, session: HeadlessSession) -> anyhow::Result<()> {
+        fs::write(self.path(id), serde_json::to_string(&session)?)?;
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> anyhow::Result<bool> {
+        let path = self.path(id);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(path)?;
+        Ok(true)
+    }
+}
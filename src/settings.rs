@@ -0,0 +1,265 @@
+//! The settings screen behind 設定 in the post-game menu. Each change is
+//! checked the way the config file is and takes effect from the next round;
+//! the changes can then be saved to the config file, keeping its comments.
+
+use crate::card;
+use crate::config::{CardStyle, GameConfig, InputMode};
+use crate::config_check::{Severity, check_values};
+use crate::config_edit;
+use crate::i18n;
+use crate::menu::pick_preset;
+use crate::prompt;
+use crate::rules::BlackjackPayout;
+use crate::t;
+use serde::Serialize;
+use std::fs;
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    CurrencyName,
+    CurrencyFullName,
+    BetAmount,
+    Preset,
+    Decks,
+    DealerHitsSoft17,
+    Surrender,
+    BlackjackPayout,
+    CardStyle,
+    InputMode,
+    ShowHints,
+    Lang,
+}
+
+impl Setting {
+    const ALL: [Setting; 12] = [
+        Setting::CurrencyName,
+        Setting::CurrencyFullName,
+        Setting::BetAmount,
+        Setting::Preset,
+        Setting::Decks,
+        Setting::DealerHitsSoft17,
+        Setting::Surrender,
+        Setting::BlackjackPayout,
+        Setting::CardStyle,
+        Setting::InputMode,
+        Setting::ShowHints,
+        Setting::Lang,
+    ];
+
+    /// Dotted key in the config file.
+    fn key(&self) -> &'static str {
+        match self {
+            Setting::CurrencyName => "currency_name",
+            Setting::CurrencyFullName => "currency_full_name",
+            Setting::BetAmount => "bet_amount",
+            Setting::Preset => "preset",
+            Setting::Decks => "rules.decks",
+            Setting::DealerHitsSoft17 => "rules.dealer_hits_soft_17",
+            Setting::Surrender => "rules.surrender",
+            Setting::BlackjackPayout => "rules.blackjack_payout",
+            Setting::CardStyle => "card_style",
+            Setting::InputMode => "input_mode",
+            Setting::ShowHints => "show_hints",
+            Setting::Lang => "lang",
+        }
+    }
+
+    fn label(&self) -> String {
+        let name = self.key().rsplit('.').next().unwrap_or_default();
+        t!(&format!("settings.{}", name))
+    }
+
+    /// The value in effect, as shown in the list.
+    fn current(&self, cfg: &GameConfig) -> String {
+        match self {
+            Setting::CurrencyName => cfg.currency_name.clone(),
+            Setting::CurrencyFullName => cfg.currency_full_name.clone(),
+            Setting::BetAmount => format!("{}{}", cfg.bet_amount, cfg.currency_name),
+            Setting::Preset => cfg.preset.map_or_else(|| t!("preset.default"), |p| p.label()),
+            Setting::Decks => cfg.rules.decks.to_string(),
+            Setting::DealerHitsSoft17 => if cfg.rules.dealer_hits_soft_17 { "H17" } else { "S17" }.to_string(),
+            Setting::Surrender => on_off(cfg.rules.surrender),
+            Setting::BlackjackPayout => cfg.rules.blackjack_payout.ratio().to_string(),
+            Setting::CardStyle => style_label(cfg.card_style),
+            Setting::InputMode => input_label(cfg.input_mode),
+            Setting::ShowHints => on_off(cfg.show_hints),
+            Setting::Lang => cfg.lang.clone(),
+        }
+    }
+
+    /// Asks for the new value; `None` leaves the setting alone.
+    fn ask(&self, cfg: &GameConfig) -> anyhow::Result<Option<toml::Value>> {
+        let label = self.label();
+        let value = match self {
+            Setting::CurrencyName => ask_text(&label, &cfg.currency_name)?,
+            Setting::CurrencyFullName => ask_text(&label, &cfg.currency_full_name)?,
+            Setting::BetAmount => {
+                let prompt = t!("settings.bet_input", min = cfg.table_min_bet, max = cfg.table_max_bet, currency = cfg.currency_name);
+                i64::from(prompt::number(&prompt, Some(cfg.bet_amount), |_| Ok(()))?).into()
+            }
+            Setting::Preset => match pick_preset(cfg)? {
+                Some(preset) => preset.key().into(),
+                None => return Ok(None),
+            },
+            Setting::Decks => i64::from(prompt::number(&label, Some(u32::from(cfg.rules.decks)), |_| Ok(()))?).into(),
+            Setting::DealerHitsSoft17 => (!cfg.rules.dealer_hits_soft_17).into(),
+            Setting::Surrender => (!cfg.rules.surrender).into(),
+            Setting::BlackjackPayout => {
+                let payouts = [BlackjackPayout::ThreeToTwo, BlackjackPayout::SixToFive, BlackjackPayout::EvenMoney];
+                let labels = payouts.map(|p| p.ratio().to_string());
+                return choose(&label, &payouts, &labels, cfg.rules.blackjack_payout);
+            }
+            Setting::CardStyle => {
+                let styles = [CardStyle::Plain, CardStyle::Unicode, CardStyle::Ascii, CardStyle::Spoken];
+                return choose(&label, &styles, &styles.map(style_label), cfg.card_style);
+            }
+            Setting::InputMode => {
+                let modes = [InputMode::Menu, InputMode::Keys];
+                return choose(&label, &modes, &modes.map(input_label), cfg.input_mode);
+            }
+            Setting::ShowHints => (!cfg.show_hints).into(),
+            Setting::Lang => {
+                let codes = ["ja".to_string(), "en".to_string()];
+                let labels = codes.clone().map(|code| t!(&format!("settings.lang_{}", code)));
+                return choose(&label, &codes, &labels, cfg.lang.clone());
+            }
+        };
+        Ok(Some(value))
+    }
+}
+
+/// Runs the settings screen until the player goes back. Accepted changes are
+/// already in `cfg` when it returns.
+pub fn edit_settings(cfg: &mut GameConfig) -> anyhow::Result<()> {
+    // changes not yet saved, by key
+    let mut changes: Vec<(String, toml::Value)> = Vec::new();
+    let mut selection = 0;
+    loop {
+        let mut items: Vec<String> = Setting::ALL
+            .iter()
+            .map(|s| t!("settings.item", name = s.label(), value = s.current(cfg)))
+            .collect();
+        items.push(t!("settings.save", path = cfg.file.display()));
+        items.push(t!("menu.back"));
+        println!();
+        selection = prompt::select(&t!("settings.prompt"), &items, selection)?;
+
+        match Setting::ALL.get(selection) {
+            Some(&setting) => {
+                let Some(value) = setting.ask(cfg)? else { continue };
+                if change(cfg, setting, value.clone()) {
+                    changes.retain(|(key, _)| key != setting.key());
+                    changes.push((setting.key().to_string(), value));
+                }
+            }
+            None if selection == Setting::ALL.len() => {
+                if let Err(e) = save(cfg, &changes) {
+                    if prompt::is_interrupted(&e) {
+                        return Err(e);
+                    }
+                    println!("{}", t!("settings.save_failed", path = cfg.file.display(), error = e));
+                    continue;
+                }
+                changes.clear();
+            }
+            None => {
+                if !changes.is_empty() {
+                    println!("{}", t!("settings.unsaved", count = changes.len()));
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Applies one change if the resulting config passes the checks; otherwise
+/// shows why, with the allowed values, and keeps the old one.
+fn change(cfg: &mut GameConfig, setting: Setting, value: toml::Value) -> bool {
+    let candidate = match cfg.with_value(setting.key(), value) {
+        Ok(candidate) => candidate,
+        Err(e) => {
+            println!("{}", t!("settings.rejected", name = setting.label(), error = e));
+            return false;
+        }
+    };
+    let problems: Vec<String> =
+        check_values(&candidate).iter().filter(|p| p.severity == Severity::Error).map(|p| p.render()).collect();
+    if !problems.is_empty() {
+        println!("{}", t!("settings.rejected", name = setting.label(), error = problems.join("\n")));
+        return false;
+    }
+    *cfg = candidate;
+    if setting == Setting::Lang {
+        // checked above, so the catalog loads
+        let _ = i18n::set_language(&cfg.lang);
+    }
+    card::set_style(cfg.card_style);
+    println!("{}", t!("settings.changed", name = setting.label(), value = setting.current(cfg)));
+    true
+}
+
+/// Writes the unsaved changes into the config file, after asking.
+fn save(cfg: &GameConfig, changes: &[(String, toml::Value)]) -> anyhow::Result<()> {
+    if changes.is_empty() {
+        println!("{}", t!("settings.nothing_to_save"));
+        return Ok(());
+    }
+    let path = &cfg.file;
+    let question = t!("settings.save_confirm", path = path.display(), count = changes.len());
+    if prompt::select(&question, &[t!("menu.yes"), t!("menu.no")], 0)? != 0 {
+        return Ok(());
+    }
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    fs::write(path, config_edit::set_values(&source, changes)?)?;
+    println!("{}", t!("settings.saved", path = path.display()));
+    Ok(())
+}
+
+fn ask_text(label: &str, current: &str) -> anyhow::Result<toml::Value> {
+    let text = prompt::text(label, current, |v| {
+        if v.trim().is_empty() { Err(t!("settings.empty")) } else { Ok(()) }
+    })?;
+    Ok(text.trim().into())
+}
+
+/// One of `options` as its config value; `None` when going back.
+fn choose<T: Serialize + PartialEq>(
+    prompt: &str,
+    options: &[T],
+    labels: &[String],
+    current: T,
+) -> anyhow::Result<Option<toml::Value>> {
+    let mut items = labels.to_vec();
+    items.push(t!("menu.back"));
+    let default = options.iter().position(|o| *o == current).unwrap_or(0);
+    let selection = prompt::select(prompt, &items, default)?;
+    match options.get(selection) {
+        Some(option) => Ok(Some(toml::Value::try_from(option)?)),
+        None => Ok(None),
+    }
+}
+
+fn on_off(on: bool) -> String {
+    if on { t!("settings.on") } else { t!("settings.off") }
+}
+
+fn style_label(style: CardStyle) -> String {
+    match style {
+        CardStyle::Plain => t!("settings.style_plain"),
+        CardStyle::Unicode => t!("settings.style_unicode"),
+        CardStyle::Ascii => t!("settings.style_ascii"),
+        CardStyle::Spoken => t!("settings.style_spoken"),
+    }
+}
+
+fn input_label(mode: InputMode) -> String {
+    match mode {
+        InputMode::Menu => t!("settings.input_menu"),
+        InputMode::Keys => t!("settings.input_keys"),
+    }
+}
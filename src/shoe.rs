@@ -0,0 +1,105 @@
+use crate::card::{Card, build_deck, shuffle_deck};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+
+/// Multi-deck shoe that persists across rounds and keeps a Hi-Lo count.
+///
+/// Serializing keeps the undealt cards in order, suits included; a restored
+/// shoe gets a fresh RNG, which only matters for the next shuffle. Cloning
+/// does the same.
+#[derive(Serialize, Deserialize)]
+pub struct Shoe {
+    cards: Vec<Card>,
+    decks: u8,
+    /// reshuffle once this many cards or fewer remain
+    cut: usize,
+    running_count: i32,
+    #[serde(skip, default = "fresh_rng")]
+    rng: StdRng,
+}
+
+fn fresh_rng() -> StdRng {
+    StdRng::seed_from_u64(rand::random())
+}
+
+impl Clone for Shoe {
+    fn clone(&self) -> Self {
+        Self {
+            cards: self.cards.clone(),
+            decks: self.decks,
+            cut: self.cut,
+            running_count: self.running_count,
+            rng: fresh_rng(),
+        }
+    }
+}
+
+impl Shoe {
+    pub fn new(decks: u8, penetration: f32, seed: u64) -> Self {
+        let decks = decks.max(1);
+        let total = usize::from(decks) * 52;
+        let dealt = (total as f32 * penetration.clamp(0.0, 1.0)) as usize;
+        let mut shoe = Self {
+            cards: Vec::with_capacity(total),
+            decks,
+            cut: total - dealt,
+            running_count: 0,
+            rng: StdRng::seed_from_u64(seed),
+        };
+        shoe.shuffle();
+        shoe
+    }
+
+    pub fn shuffle(&mut self) {
+        self.cards.clear();
+        for _ in 0..self.decks {
+            self.cards.extend(build_deck());
+        }
+        shuffle_deck(&mut self.cards, &mut self.rng);
+        self.running_count = 0;
+    }
+
+    /// True once the cut card has come out; shuffle before the next round.
+    pub fn needs_shuffle(&self) -> bool {
+        self.cards.len() <= self.cut
+    }
+
+    /// Deals a face-up card, reshuffling if the shoe ran dry mid-round.
+    pub fn draw(&mut self) -> Card {
+        let card = self.draw_face_down();
+        self.reveal(card);
+        card
+    }
+
+    /// Deals a card that is not counted until `reveal` is called.
+    pub fn draw_face_down(&mut self) -> Card {
+        if self.cards.is_empty() {
+            self.shuffle();
+        }
+        self.cards.pop().unwrap()
+    }
+
+    pub fn reveal(&mut self, card: Card) {
+        self.running_count += hi_lo(card);
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Running count per deck still in the shoe.
+    pub fn true_count(&self) -> f32 {
+        let decks_left = (self.cards.len() as f32 / 52.0).max(0.5);
+        self.running_count as f32 / decks_left
+    }
+}
+
+/// Hi-Lo tag: 2–6 count +1, 7–9 count 0, tens and aces count −1.
+pub fn hi_lo(card: Card) -> i32 {
+    match card.value() {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1,
+    }
+}
@@ -0,0 +1,263 @@
+use crate::advisor::{AdvisorMode, advised_bet};
+use crate::betting::BettingSystem;
+use crate::config::GameConfig;
+use crate::deviations::count_strategy;
+use crate::player::PlayerState;
+use crate::round::{Round, Wager};
+use crate::shoe::Shoe;
+use crate::strategy::basic_strategy;
+use crate::t;
+use serde::Serialize;
+
+pub struct SimOptions {
+    pub sessions: u32,
+    pub rounds: u32,
+    pub seed: u64,
+}
+
+/// Aggregated result of many sessions played with one betting system.
+#[derive(Serialize)]
+pub struct SystemReport {
+    pub system: BettingSystem,
+    pub ruin_rate: f64,
+    pub avg_final_bank: f64,
+    pub avg_bet: f64,
+    pub max_bet: u32,
+    /// net result divided by the total amount wagered
+    pub return_rate: f64,
+}
+
+/// Result of counting-based bet sizing over many sessions.
+#[derive(Serialize)]
+pub struct SpreadReport {
+    pub mode: AdvisorMode,
+    pub hands: u64,
+    pub avg_bet: f64,
+    /// expected result per 100 hands
+    pub win_rate: f64,
+    /// standard deviation per 100 hands
+    pub std_dev: f64,
+    pub ruin_rate: f64,
+}
+
+fn new_shoe(cfg: &GameConfig, seed: u64) -> Shoe {
+    Shoe::new(cfg.rules.decks, cfg.rules.penetration, seed)
+}
+
+/// Plays one round with basic strategy, or as a card counter using the index
+/// plays; returns the net result and amount wagered. Rounds are settled as
+/// the plain house game: `player_edge` would have every system beat the house.
+pub fn play_auto_round(shoe: &mut Shoe, bet: u32, bankroll: u32, cfg: &GameConfig, counting: bool) -> (i64, u32) {
+    if shoe.needs_shuffle() {
+        shoe.shuffle();
+    }
+
+    let mut round = Round::deal(shoe, &[Wager { bet, bankroll }], &cfg.rules);
+    let insure = counting && cfg.deviations.take_insurance(shoe.true_count());
+    round.resolve_insurance(shoe, insure);
+    while let Some(hand) = round.active_hand() {
+        let (up, legal) = (round.dealer_up_card(), round.legal_actions());
+        let action = if counting {
+            count_strategy(&hand.cards, up, &legal, shoe.true_count(), &cfg.deviations)
+        } else {
+            basic_strategy(&hand.cards, up, &legal)
+        };
+        round
+            .apply(shoe, action)
+            .expect("basic strategy only picks legal actions");
+    }
+    round.play_dealer(shoe);
+    (round.net(0, 0.0), round.total_bet(0))
+}
+
+pub fn simulate_system(cfg: &GameConfig, system: BettingSystem, opts: &SimOptions) -> SystemReport {
+    let mut shoe = new_shoe(cfg, opts.seed);
+    let mut ruined = 0u32;
+    let mut final_banks = 0u64;
+    let mut rounds_played = 0u64;
+    let mut wagered = 0u64;
+    let mut net = 0i64;
+    let mut max_bet = 0u32;
+
+    for _ in 0..opts.sessions {
+        let mut player = PlayerState::with_system(cfg, system);
+        for _ in 0..opts.rounds {
+            if !player.can_play(cfg) {
+                ruined += 1;
+                break;
+            }
+            max_bet = max_bet.max(player.bet);
+            let (round_net, round_wager) = play_auto_round(&mut shoe, player.bet, player.bank, cfg, false);
+            player.settle(round_net, cfg);
+            rounds_played += 1;
+            wagered += u64::from(round_wager);
+            net += round_net;
+        }
+        final_banks += u64::from(player.bank);
+    }
+
+    let sessions = f64::from(opts.sessions.max(1));
+    SystemReport {
+        system,
+        ruin_rate: f64::from(ruined) / sessions,
+        avg_final_bank: final_banks as f64 / sessions,
+        avg_bet: wagered as f64 / rounds_played.max(1) as f64,
+        max_bet,
+        return_rate: net as f64 / wagered.max(1) as f64,
+    }
+}
+
+/// Counting bot: sizes every bet from the true count with the given advisor
+/// mode and plays the index plays.
+pub fn simulate_spread(cfg: &GameConfig, mode: AdvisorMode, opts: &SimOptions) -> SpreadReport {
+    let mut shoe = new_shoe(cfg, opts.seed);
+    let mut ruined = 0u32;
+    let mut hands = 0u64;
+    let mut wagered = 0u64;
+    let mut sum = 0f64;
+    let mut sum_sq = 0f64;
+
+    for _ in 0..opts.sessions {
+        let mut bank = cfg.player_starting_bank;
+        for _ in 0..opts.rounds {
+            if shoe.needs_shuffle() {
+                shoe.shuffle();
+            }
+            let bet = advised_bet(cfg, mode, shoe.true_count(), bank).unwrap_or(cfg.bet_amount.min(bank));
+            if bet == 0 || bank < cfg.table_min_bet {
+                ruined += 1;
+                break;
+            }
+            let (net, wager) = play_auto_round(&mut shoe, bet, bank, cfg, true);
+            bank = (i64::from(bank) + net).max(0) as u32;
+            hands += 1;
+            wagered += u64::from(wager);
+            sum += net as f64;
+            sum_sq += (net * net) as f64;
+        }
+    }
+
+    let n = hands.max(1) as f64;
+    let mean = sum / n;
+    let variance = (sum_sq / n - mean * mean).max(0.0);
+    SpreadReport {
+        mode,
+        hands,
+        avg_bet: wagered as f64 / n,
+        win_rate: mean * 100.0,
+        std_dev: variance.sqrt() * 10.0,
+        ruin_rate: f64::from(ruined) / f64::from(opts.sessions.max(1)),
+    }
+}
+
+pub fn print_reports(cfg: &GameConfig, opts: &SimOptions, reports: &[SystemReport]) {
+    let cur = &cfg.currency_name;
+    println!("{}", t!("simulation.title"));
+    println!(
+        "{}",
+        t!(
+            "simulation.sessions",
+            sessions = opts.sessions,
+            rounds = opts.rounds,
+            bank = cfg.player_starting_bank,
+            currency = cur,
+            seed = opts.seed
+        )
+    );
+    println!(
+        "{}",
+        t!(
+            "simulation.limits",
+            min = cfg.table_min_bet,
+            max = cfg.table_max_bet,
+            bet = cfg.bet_amount,
+            currency = cur
+        )
+    );
+    println!();
+    println!(
+        "{:<20} {:>8} {:>12} {:>10} {:>10} {:>10}",
+        t!("simulation.system"),
+        t!("simulation.ruin_rate"),
+        t!("simulation.avg_final_bank"),
+        t!("simulation.avg_bet"),
+        t!("simulation.max_bet"),
+        t!("simulation.return_rate")
+    );
+    for r in reports {
+        println!(
+            "{:<20} {:>7.1}% {:>12.1} {:>10.1} {:>10} {:>9.2}%",
+            r.system.label(),
+            r.ruin_rate * 100.0,
+            r.avg_final_bank,
+            r.avg_bet,
+            r.max_bet,
+            r.return_rate * 100.0
+        );
+    }
+    println!();
+    println!("{}", t!("simulation.note"));
+}
+
+pub fn print_spread_report(cfg: &GameConfig, opts: &SimOptions, r: &SpreadReport) {
+    let adv = &cfg.bet_advisor;
+    let cur = &cfg.currency_name;
+    println!("{}", t!("simulation.spread_title"));
+    println!(
+        "{}",
+        t!(
+            "simulation.sessions",
+            sessions = opts.sessions,
+            rounds = opts.rounds,
+            bank = cfg.player_starting_bank,
+            currency = cur,
+            seed = opts.seed
+        )
+    );
+    println!(
+        "{}",
+        t!(
+            "simulation.shoe",
+            decks = cfg.rules.decks,
+            penetration = format!("{:.0}", cfg.rules.penetration * 100.0),
+            edge = format!("{:.2}", cfg.rules.house_edge()),
+            mode = r.mode.label()
+        )
+    );
+    match r.mode {
+        AdvisorMode::Ramp => println!(
+            "{}",
+            t!(
+                "simulation.ramp",
+                units = adv.units_per_count,
+                max = adv.max_units,
+                unit = cfg.bet_amount,
+                currency = cur
+            )
+        ),
+        AdvisorMode::Kelly => println!(
+            "{}",
+            t!(
+                "simulation.kelly",
+                fraction = adv.kelly_fraction,
+                base = format!("{:.2}", adv.base_edge * 100.0),
+                per_count = format!("{:.2}", adv.edge_per_count * 100.0)
+            )
+        ),
+        AdvisorMode::Off => println!("{}", t!("simulation.flat", bet = cfg.bet_amount, currency = cur)),
+    }
+    println!();
+    println!("{}", t!("simulation.hands", hands = r.hands));
+    println!("{}", t!("simulation.avg_bet_line", bet = format!("{:.1}", r.avg_bet), currency = cur));
+    println!("{}", t!("simulation.win_rate", amount = format!("{:+.1}", r.win_rate), currency = cur));
+    println!("{}", t!("simulation.std_dev", amount = format!("{:.1}", r.std_dev), currency = cur));
+    println!("{}", t!("simulation.ruin_rate_line", percent = format!("{:.1}", r.ruin_rate * 100.0)));
+    println!(
+        "{}",
+        t!(
+            "simulation.play",
+            plays = cfg.deviations.plays.len(),
+            insurance = format!("{:+}", cfg.deviations.insurance_index)
+        )
+    );
+}
@@ -0,0 +1,111 @@
+use crate::events::{EventSink, TableEvent};
+use crate::t;
+use std::io::ErrorKind;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+/// Events a spectator may fall behind by before it is dropped.
+const BACKLOG: usize = 256;
+/// How often a watcher's thread stops reading to send queued events.
+const POLL: Duration = Duration::from_millis(50);
+/// A spectator that takes longer than this to accept a frame is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct Watchers {
+    /// one queue per spectator, drained by that spectator's thread
+    queues: Vec<SyncSender<String>>,
+    /// the latest `table` event, so late arrivals know who is seated
+    seats: Option<String>,
+}
+
+/// Read-only WebSocket feed: every table event goes out to every spectator
+/// as one JSON text frame. Each spectator has its own thread, so a slow or
+/// stalled one never holds up the table.
+pub struct SpectatorFeed {
+    watchers: Arc<Mutex<Watchers>>,
+}
+
+impl SpectatorFeed {
+    pub fn bind(addr: &str) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        println!("{}", t!("spectator.listening", addr = listener.local_addr()?));
+        let watchers = Arc::new(Mutex::new(Watchers::default()));
+        let shared = Arc::clone(&watchers);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = Arc::clone(&shared);
+                // the handshake blocks on the client, so keep it off the accept loop
+                thread::spawn(move || {
+                    let _ = stream.set_nodelay(true);
+                    let Ok(socket) = tungstenite::accept(stream) else {
+                        return;
+                    };
+                    let (tx, rx) = mpsc::sync_channel(BACKLOG);
+                    {
+                        let mut watchers = shared.lock().unwrap();
+                        if let Some(seats) = &watchers.seats {
+                            let _ = tx.try_send(seats.clone());
+                        }
+                        watchers.queues.push(tx);
+                    }
+                    watch(socket, rx);
+                });
+            }
+        });
+        Ok(Self { watchers })
+    }
+}
+
+/// Sends one spectator its queued events until it closes, errors or the
+/// feed drops it. Reading in between answers pings and notices the close
+/// frame; anything else a spectator sends is ignored.
+fn watch(mut socket: WebSocket<TcpStream>, rx: Receiver<String>) {
+    let stream = socket.get_ref();
+    if stream.set_read_timeout(Some(POLL)).is_err() || stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+        return;
+    }
+    loop {
+        match socket.read() {
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(_) => return,
+        }
+        loop {
+            match rx.try_recv() {
+                Ok(text) => {
+                    if socket.send(Message::text(text)).is_err() {
+                        return;
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+        // pongs queued by `read` go out here
+        if socket.flush().is_err() {
+            return;
+        }
+    }
+    // finish the closing handshake the spectator started
+    let _ = socket.flush();
+}
+
+impl EventSink for SpectatorFeed {
+    fn publish(&mut self, event: &TableEvent) {
+        let Ok(text) = serde_json::to_string(event) else {
+            return;
+        };
+        let mut watchers = self.watchers.lock().unwrap();
+        if matches!(event, TableEvent::Table { .. }) {
+            watchers.seats = Some(text.clone());
+        }
+        // spectators that went away, or fell too far behind, are dropped
+        watchers.queues.retain(|queue| queue.try_send(text.clone()).is_ok());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::card::{Card, hand_value};
+use crate::game_action::GameAction;
+
+/// Table entries as written in `docs/std_move.md`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Move {
+    Hit,
+    Stand,
+    /// Double if allowed, otherwise hit
+    Double,
+    /// Double if allowed, otherwise stand
+    DoubleOrStand,
+    Split,
+    /// Surrender if allowed, otherwise hit
+    Surrender,
+}
+
+/// Basic strategy decision for `hand` against the dealer's up-card.
+pub fn basic_strategy(hand: &[Card], dealer_up: Card, legal: &[GameAction]) -> GameAction {
+    let up = dealer_up.value();
+    let can = |a: GameAction| legal.contains(&a);
+
+    let mv = if can(GameAction::Split) {
+        pair_move(hand[0].value(), up)
+    } else {
+        None
+    }
+    .unwrap_or_else(|| {
+        if is_soft(hand) {
+            soft_move(hand_value(hand), up)
+        } else {
+            hard_move(hand_value(hand), up)
+        }
+    });
+
+    match mv {
+        Move::Hit => GameAction::Hit,
+        Move::Stand => GameAction::Stand,
+        Move::Double if can(GameAction::DoubleDown) => GameAction::DoubleDown,
+        Move::Double => GameAction::Hit,
+        Move::DoubleOrStand if can(GameAction::DoubleDown) => GameAction::DoubleDown,
+        Move::DoubleOrStand => GameAction::Stand,
+        Move::Split => GameAction::Split,
+        Move::Surrender if can(GameAction::Surrender) => GameAction::Surrender,
+        Move::Surrender => GameAction::Hit,
+    }
+}
+
+/// True when an ace is still counted as 11.
+pub fn is_soft(hand: &[Card]) -> bool {
+    let hard: u8 = hand.iter().map(|c| if c.is_ace() { 1 } else { c.value() }).sum();
+    hand.iter().any(|c| c.is_ace()) && hard + 10 <= 21
+}
+
+/// Split decision for a pair; `None` means play the hand as a total.
+fn pair_move(card: u8, up: u8) -> Option<Move> {
+    let split = match card {
+        11 | 8 => true,
+        2 | 3 | 7 => up <= 7,
+        4 => matches!(up, 5 | 6),
+        6 => up <= 6,
+        9 => !matches!(up, 7 | 10 | 11),
+        _ => false,
+    };
+    split.then_some(Move::Split)
+}
+
+fn soft_move(total: u8, up: u8) -> Move {
+    match total {
+        13 | 14 if matches!(up, 5 | 6) => Move::Double,
+        15 | 16 if (4..=6).contains(&up) => Move::Double,
+        17 if (3..=6).contains(&up) => Move::Double,
+        18 if (3..=6).contains(&up) => Move::DoubleOrStand,
+        18 if up >= 9 => Move::Hit,
+        18..=21 => Move::Stand,
+        _ => Move::Hit,
+    }
+}
+
+fn hard_move(total: u8, up: u8) -> Move {
+    match total {
+        0..=8 => Move::Hit,
+        9 if (3..=6).contains(&up) => Move::Double,
+        10 if up <= 9 => Move::Double,
+        11 if up <= 10 => Move::Double,
+        12 if (4..=6).contains(&up) => Move::Stand,
+        15 if up == 10 => Move::Surrender,
+        16 if up >= 9 => Move::Surrender,
+        13..=16 if up <= 6 => Move::Stand,
+        17..=21 => Move::Stand,
+        _ => Move::Hit,
+    }
+}
@@ -0,0 +1,109 @@
+use crate::advisor::{AdvisorMode, advised_bet};
+use crate::betting::BettingSystem;
+use crate::bots::Controller;
+use crate::config::GameConfig;
+use crate::player::{PlayerState, SavedPlayer};
+use crate::t;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_SEATS: usize = 7;
+
+/// Seats at the table (`[table]` in game_config.toml).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TableConfig {
+    /// pause between bot actions so they can be followed
+    pub bot_delay_ms: u64,
+    /// in dealing order, at most `MAX_SEATS`
+    pub seats: Vec<SeatConfig>,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self { bot_delay_ms: 700, seats: vec![SeatConfig::default()] }
+    }
+}
+
+impl TableConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.seats.is_empty() || self.seats.len() > MAX_SEATS {
+            anyhow::bail!(t!("config.seats_range", max = MAX_SEATS, seats = self.seats.len()));
+        }
+        Ok(())
+    }
+
+    /// Hot-seat play: replaces the human seats with one per name, seated
+    /// before the computer players.
+    pub fn seat_humans(&mut self, names: &[String]) -> anyhow::Result<()> {
+        self.seats.retain(|s| !s.player.is_human());
+        let humans = names.iter().map(|name| SeatConfig { name: name.trim().to_string(), ..SeatConfig::default() });
+        self.seats.splice(0..0, humans);
+        self.validate()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SeatConfig {
+    pub name: String,
+    pub player: Controller,
+    /// starting bankroll; `player_starting_bank` when omitted
+    pub bank: Option<u32>,
+}
+
+impl Default for SeatConfig {
+    fn default() -> Self {
+        Self { name: t!("game.player"), player: Controller::Human, bank: None }
+    }
+}
+
+pub struct Seat {
+    pub name: String,
+    pub controller: Controller,
+    pub player: PlayerState,
+}
+
+impl Seat {
+    fn new(cfg: &GameConfig, seat: &SeatConfig) -> Self {
+        let mut player = PlayerState::with_system(cfg, Self::system(cfg, seat.player));
+        if let Some(bank) = seat.bank {
+            player.bank = bank;
+            player.bet = player.suggested_bet(cfg);
+        }
+        Self { name: seat.name.clone(), controller: seat.player, player }
+    }
+
+    /// A seat saved between launches, with its bankroll, bet and betting
+    /// progression.
+    pub fn resume(cfg: &GameConfig, name: String, controller: Controller, player: &SavedPlayer) -> Self {
+        Self { name, controller, player: PlayerState::resume(Self::system(cfg, controller), player) }
+    }
+
+    /// Humans use the configured betting system, bots bet flat.
+    fn system(cfg: &GameConfig, controller: Controller) -> BettingSystem {
+        if controller.is_human() { cfg.betting_system } else { BettingSystem::Flat }
+    }
+
+    /// Bet for the coming round; the counter follows its bet ramp.
+    pub fn wager(&self, cfg: &GameConfig, true_count: f32) -> u32 {
+        match self.controller {
+            Controller::Counter => advised_bet(cfg, AdvisorMode::Ramp, true_count, self.player.bank)
+                .unwrap_or(self.player.bet),
+            _ => self.player.bet,
+        }
+    }
+}
+
+pub struct Table {
+    pub seats: Vec<Seat>,
+}
+
+impl Table {
+    pub fn new(cfg: &GameConfig) -> Self {
+        Self { seats: cfg.table.seats.iter().map(|s| Seat::new(cfg, s)).collect() }
+    }
+
+    pub fn has_humans(&self) -> bool {
+        self.seats.iter().any(|s| s.controller.is_human())
+    }
+}
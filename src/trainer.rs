@@ -0,0 +1,99 @@
+use crate::card::print_hand;
+use crate::config::GameConfig;
+use crate::deviations::count_strategy;
+use crate::game_action::GameAction;
+use crate::round::{Round, Wager};
+use crate::shoe::Shoe;
+use crate::prompt;
+use crate::t;
+
+/// Strategy drill: deals from a live shoe and checks each decision against
+/// basic strategy plus the index plays for the current true count.
+pub fn run_trainer(cfg: &GameConfig) -> anyhow::Result<()> {
+    let mut shoe = Shoe::new(cfg.rules.decks, cfg.rules.penetration, rand::random());
+    let (mut correct, mut asked) = (0u32, 0u32);
+
+    println!("\n{}", t!("trainer.title"));
+    println!("{}", t!("trainer.intro"));
+
+    loop {
+        if shoe.needs_shuffle() {
+            shoe.shuffle();
+            println!("{}", t!("trainer.shuffled"));
+        }
+        let mut round = Round::deal(&mut shoe, &[Wager { bet: 2, bankroll: u32::MAX }], &cfg.rules);
+        let tc = shoe.true_count();
+
+        println!("\n{}", t!("trainer.question", number = asked + 1, tc = format!("{:+.1}", tc)));
+        print_hand(&t!("game.dealer"), &round.dealer, true);
+        print_hand(&t!("game.player"), &round.spots[0].hands[0].cards, false);
+
+        let quit = if round.pending_insurance().is_some() {
+            ask_insurance(cfg, &mut shoe, &mut round, tc, &mut correct, &mut asked)?
+        } else {
+            false
+        };
+        if quit {
+            break;
+        }
+
+        if let Some(hand) = round.active_hand() {
+            let legal = round.legal_actions();
+            let mut items = GameAction::menu_items(&legal);
+            items.push(t!("trainer.quit"));
+            let selection = prompt::select(&t!("trainer.prompt"), &items, 0)?;
+            if selection == legal.len() {
+                break;
+            }
+
+            let up = round.dealer_up_card();
+            let expected = count_strategy(&hand.cards, up, &legal, tc, &cfg.deviations);
+            asked += 1;
+            if legal[selection] == expected {
+                correct += 1;
+                println!("{}", t!("trainer.correct"));
+            } else {
+                println!("{}", t!("trainer.wrong", answer = expected.label()));
+            }
+            if let Some(play) = cfg.deviations.lookup(&hand.cards, up, &legal, tc) {
+                println!("{}", t!("trainer.index_play", play = play.describe()));
+            }
+        } else {
+            println!("{}", t!("trainer.redeal"));
+        }
+        // the hole card is seen before the next deal, keeping the count honest
+        round.reveal_hole(&mut shoe);
+    }
+
+    if asked > 0 {
+        let percent = format!("{:.0}", f64::from(correct) * 100.0 / f64::from(asked));
+        println!("\n{}", t!("trainer.score", correct = correct, asked = asked, percent = percent));
+    }
+    Ok(())
+}
+
+/// Returns true when the player chose to stop the drill.
+fn ask_insurance(
+    cfg: &GameConfig,
+    shoe: &mut Shoe,
+    round: &mut Round,
+    tc: f32,
+    correct: &mut u32,
+    asked: &mut u32,
+) -> anyhow::Result<bool> {
+    let selection = prompt::select(&t!("trainer.insurance_prompt"), &[t!("menu.yes"), t!("menu.no"), t!("trainer.quit")], 1)?;
+    if selection == 2 {
+        return Ok(true);
+    }
+
+    let expected = cfg.deviations.take_insurance(tc);
+    *asked += 1;
+    if (selection == 0) == expected {
+        *correct += 1;
+        println!("{}", t!("trainer.correct"));
+    } else {
+        println!("{}", t!("trainer.wrong_insurance", index = format!("{:+}", cfg.deviations.insurance_index)));
+    }
+    round.resolve_insurance(shoe, selection == 0);
+    Ok(false)
+}
@@ -0,0 +1,250 @@
+use crate::blackjack::HandOutcome;
+use crate::card::Card;
+use crate::config::GameConfig;
+use crate::headless::{HandState, HeadlessSession, Phase, SessionState};
+use crate::rules::BlackjackPayout;
+use crate::t;
+use ratatui::crossterm::event::{self, KeyCode};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, List, ListItem, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+/// Below this the table cannot be drawn; a notice is shown instead.
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 18;
+const HISTORY_WIDTH: u16 = 26;
+
+struct App {
+    session: HeadlessSession,
+    /// amount for the next deal, adjusted with the arrow keys
+    bet: u32,
+    message: String,
+    quit: bool,
+}
+
+/// Full-screen table: the dealer on top, one panel per hand, bankroll and
+/// shoe in a status bar and the settled rounds in a sidebar.
+pub fn run_tui(cfg: &GameConfig, seed: u64) -> anyhow::Result<()> {
+    let mut app = App {
+        session: HeadlessSession::new(cfg.clone(), seed),
+        bet: cfg.bet_amount,
+        message: t!("tui.welcome"),
+        quit: false,
+    };
+    ratatui::run(|terminal| app.run(terminal))
+}
+
+impl App {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        while !self.quit {
+            // a resize is just another event; every pass redraws at the new size
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Some(key) = event::read()?.as_key_press_event() {
+                self.handle_key(key.code);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        let cfg = &self.session.config;
+        let step = cfg.table_min_bet.max(1);
+        let max_bet = cfg.table_max_bet.min(self.session.bank);
+        let result = match (self.session.phase(), code) {
+            (_, KeyCode::Char('q') | KeyCode::Esc) => {
+                self.quit = true;
+                return;
+            }
+            (Phase::Betting, KeyCode::Left | KeyCode::Down | KeyCode::Char('-')) => {
+                self.bet = self.bet.saturating_sub(step).max(cfg.table_min_bet);
+                return;
+            }
+            (Phase::Betting, KeyCode::Right | KeyCode::Up | KeyCode::Char('+')) => {
+                self.bet = (self.bet + step).min(max_bet);
+                return;
+            }
+            (Phase::Betting, KeyCode::Enter | KeyCode::Char(' ')) => {
+                self.bet = self.bet.clamp(cfg.table_min_bet, max_bet);
+                self.session.bet(self.bet)
+            }
+            (Phase::Insurance, KeyCode::Char('y')) => self.session.insurance(true),
+            (Phase::Insurance, KeyCode::Char('n')) => self.session.insurance(false),
+            (Phase::PlayerTurn, KeyCode::Char(c)) => {
+                match self.session.state().allowed_actions.into_iter().find(|&a| a.shortcut() == c) {
+                    Some(action) => self.session.act(action),
+                    None => return,
+                }
+            }
+            _ => return,
+        };
+        self.message = match result {
+            Ok(()) => self.phase_message(),
+            Err(e) => e.to_string(),
+        };
+    }
+
+    fn phase_message(&self) -> String {
+        let state = self.session.state();
+        match (state.phase, &state.last_result) {
+            (Phase::Betting | Phase::GameOver, Some(result)) => {
+                t!("tui.round_result", round = result.round, net = format!("{:+}", result.net), currency = state.currency)
+            }
+            (Phase::Insurance, _) => {
+                t!("play.insurance_prompt", cost = state.insurance_cost.unwrap_or(0), currency = state.currency)
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+            let notice = t!(
+                "tui.too_small",
+                min = format!("{}x{}", MIN_WIDTH, MIN_HEIGHT),
+                current = format!("{}x{}", area.width, area.height)
+            );
+            frame.render_widget(Paragraph::new(notice).wrap(Wrap { trim: true }), area);
+            return;
+        }
+        let state = self.session.state();
+        let [table, history] = Layout::horizontal([Constraint::Min(0), Constraint::Length(HISTORY_WIDTH)]).areas(area);
+        let [dealer, hands, status, help] =
+            Layout::vertical([Constraint::Length(5), Constraint::Min(6), Constraint::Length(3), Constraint::Length(3)])
+                .areas(table);
+
+        self.draw_dealer(frame, dealer, &state);
+        self.draw_hands(frame, hands, &state);
+        self.draw_status(frame, status, &state);
+        self.draw_help(frame, help, &state);
+        self.draw_history(frame, history);
+    }
+
+    fn draw_dealer(&self, frame: &mut Frame, area: Rect, state: &SessionState) {
+        let total = if state.dealer.is_empty() { String::new() } else { t!("tui.total", total = state.dealer_total) };
+        let block = Block::bordered().title(format!(" {} ", t!("game.dealer"))).title_bottom(Line::from(total).right_aligned());
+        let cards = Line::from(card_spans(state.dealer.iter().copied()));
+        frame.render_widget(Paragraph::new(vec![Line::default(), cards]).block(block).alignment(Alignment::Center), area);
+    }
+
+    fn draw_hands(&self, frame: &mut Frame, area: Rect, state: &SessionState) {
+        if state.hands.is_empty() {
+            let block = Block::bordered().title(format!(" {} ", t!("game.player")));
+            frame.render_widget(Paragraph::new(t!("tui.waiting")).block(block).alignment(Alignment::Center), area);
+            return;
+        }
+        let columns = vec![Constraint::Ratio(1, state.hands.len() as u32); state.hands.len()];
+        let panels = Layout::horizontal(columns).split(area);
+        for (i, (hand, panel)) in state.hands.iter().zip(panels.iter()).enumerate() {
+            let active = state.active_hand == Some(i);
+            frame.render_widget(hand_panel(hand, i, state.hands.len(), active), *panel);
+        }
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect, state: &SessionState) {
+        let currency = &state.currency;
+        let bet = if state.phase == Phase::PlayerTurn || state.phase == Phase::Insurance {
+            state.hands.iter().map(|h| h.bet).sum()
+        } else {
+            self.bet
+        };
+        let line = Line::from(vec![
+            Span::raw(format!(" {} ", t!("tui.bank"))),
+            Span::styled(format!("{}{}", state.bank, currency), Style::new().bold()),
+            Span::raw(format!("   {} ", t!("tui.bet"))),
+            Span::styled(format!("{}{}", bet, currency), Style::new().bold()),
+            Span::raw(format!("   {}", t!("tui.limits", min = state.min_bet, max = state.max_bet, currency = currency))),
+            Span::raw(format!("   {}", t!("tui.shoe", cards = self.session.shoe_remaining()))),
+        ]);
+        frame.render_widget(Paragraph::new(line).block(Block::bordered()), area);
+    }
+
+    fn draw_help(&self, frame: &mut Frame, area: Rect, state: &SessionState) {
+        let keys = match state.phase {
+            Phase::Betting => t!("tui.keys_betting"),
+            Phase::Insurance => t!("tui.keys_insurance"),
+            Phase::PlayerTurn => {
+                let actions: Vec<String> =
+                    state.allowed_actions.iter().map(|&a| format!("{} {}", a.shortcut(), a.name())).collect();
+                format!("{}  {}", actions.join("  "), t!("tui.key_quit"))
+            }
+            Phase::GameOver => t!("tui.game_over"),
+        };
+        let text = vec![Line::from(self.message.as_str().yellow()), Line::from(keys.dim())];
+        frame.render_widget(Paragraph::new(text), area);
+    }
+
+    fn draw_history(&self, frame: &mut Frame, area: Rect) {
+        let currency = &self.session.config.currency_name;
+        // newest first, as many as fit
+        let items: Vec<ListItem> = self
+            .session
+            .history
+            .iter()
+            .rev()
+            .take(area.height.saturating_sub(2) as usize)
+            .map(|r| {
+                let color = match r.net {
+                    n if n > 0 => Color::Green,
+                    n if n < 0 => Color::Red,
+                    _ => Color::Gray,
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("#{:<3}", r.round)),
+                    Span::styled(format!("{:>+6}", r.net), Style::new().fg(color)),
+                    Span::raw(format!(" {:>6}{}", r.bank, currency)),
+                ]))
+            })
+            .collect();
+        frame.render_widget(List::new(items).block(Block::bordered().title(format!(" {} ", t!("tui.history")))), area);
+    }
+}
+
+fn card_spans(cards: impl Iterator<Item = Option<Card>>) -> Vec<Span<'static>> {
+    cards
+        .flat_map(|card| {
+            let face = match card {
+                Some(card) => {
+                    let color = if card.1.is_red() { Color::Red } else { Color::Reset };
+                    Span::styled(format!("[{:>2}{}]", card.symbol(), card.1.symbol()), Style::new().bold().fg(color))
+                }
+                None => Span::styled("[???]", Style::new().dim()),
+            };
+            [face, Span::raw(" ")]
+        })
+        .collect()
+}
+
+fn hand_panel(hand: &HandState, index: usize, count: usize, active: bool) -> Paragraph<'static> {
+    let title = if count > 1 { format!(" {} ", t!("tui.hand_n", hand = index + 1)) } else { format!(" {} ", t!("tui.hand")) };
+    let mut block = Block::bordered().title(title);
+    if active {
+        block = block.border_type(BorderType::Thick).border_style(Style::new().fg(Color::Yellow));
+    }
+    let mut total = t!("tui.total", total = hand.total);
+    if hand.soft {
+        total.push_str(&t!("tui.soft_suffix"));
+    }
+    if hand.doubled {
+        total.push_str(&t!("tui.doubled_suffix"));
+    }
+    let mut lines = vec![
+        Line::default(),
+        Line::from(card_spans(hand.cards.iter().map(|&c| Some(c)))),
+        Line::from(total),
+        Line::from(format!("{} {}", t!("tui.bet"), hand.bet)),
+    ];
+    if let Some(outcome) = hand.outcome {
+        let color = match outcome.payout(2, BlackjackPayout::EvenMoney) {
+            n if n > 0 => Color::Green,
+            n if n < 0 => Color::Red,
+            _ => Color::Gray,
+        };
+        lines.push(Line::from(outcome.label()).style(Style::new().fg(color).add_modifier(Modifier::BOLD)));
+    } else if hand.surrendered {
+        lines.push(Line::from(HandOutcome::Surrender.label()));
+    }
+    Paragraph::new(lines).block(block).alignment(Alignment::Center)
+}
@@ -0,0 +1,59 @@
+//! JavaScript API for the browser build (`--features wasm`, see
+//! `web/README.md`). State crosses as JSON text in the `SessionState`
+//! shape the REST API uses; errors are thrown as JS `Error`s.
+
+use crate::config::GameConfig;
+use crate::game_action::GameAction;
+use crate::headless::HeadlessSession;
+use wasm_bindgen::prelude::*;
+
+fn js_error(e: impl std::fmt::Display) -> JsError {
+    JsError::new(&e.to_string())
+}
+
+/// One seat against the dealer with the default rules.
+#[wasm_bindgen]
+pub struct Game {
+    session: HeadlessSession,
+}
+
+#[wasm_bindgen]
+impl Game {
+    /// Shuffles with the browser's `crypto.getRandomValues`.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Game {
+        Game { session: HeadlessSession::new(GameConfig::default(), rand::random()) }
+    }
+
+    /// Same cards every time, for tests.
+    #[wasm_bindgen(js_name = withSeed)]
+    pub fn with_seed(seed: u32) -> Game {
+        Game { session: HeadlessSession::new(GameConfig::default(), u64::from(seed)) }
+    }
+
+    /// Current state as JSON (`phase`, `bank`, `dealer`, `hands`, `allowed_actions`, ...).
+    pub fn state(&self) -> Result<String, JsError> {
+        serde_json::to_string(&self.session.state()).map_err(js_error)
+    }
+
+    pub fn bet(&mut self, amount: u32) -> Result<(), JsError> {
+        self.session.bet(amount).map_err(js_error)
+    }
+
+    pub fn insurance(&mut self, take: bool) -> Result<(), JsError> {
+        self.session.insurance(take).map_err(js_error)
+    }
+
+    /// `action` is `hit`, `stand`, `double_down`, `split` or `surrender`.
+    pub fn act(&mut self, action: &str) -> Result<(), JsError> {
+        let action: GameAction =
+            serde_json::from_value(serde_json::Value::String(action.to_string())).map_err(js_error)?;
+        self.session.act(action).map_err(js_error)
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,87 @@
+import init, { Game } from "./pkg/veintiuna.js";
+
+const LABELS = {
+  hit: "ヒット",
+  stand: "スタンド",
+  double_down: "ダブルダウン",
+  split: "スプリット",
+  surrender: "サレンダー",
+};
+
+const OUTCOMES = {
+  bust: "バスト - 負け",
+  dealer_bust: "ディーラーがバスト - 勝ち",
+  blackjack: "ブラックジャック - 勝ち",
+  dealer_blackjack: "ディーラーブラックジャック - 負け",
+  both_blackjack: "両方ブラックジャック - 引き分け",
+  win: "勝ち",
+  edge_win: "勝ち (エッジ)",
+  lose: "負け",
+  push: "引き分け",
+  surrender: "サレンダー - 半額返却",
+};
+
+const SUITS = { S: "♠", H: "♥", D: "♦", C: "♣" };
+
+// "10H" -> "10♥"
+const card = (c) => c.slice(0, -1) + SUITS[c.slice(-1)];
+
+await init();
+const game = new Game();
+const $ = (id) => document.getElementById(id);
+
+function button(label, onClick) {
+  const b = document.createElement("button");
+  b.textContent = label;
+  b.onclick = () => {
+    try {
+      onClick();
+      $("message").textContent = "";
+    } catch (e) {
+      $("message").textContent = e.message;
+    }
+    render();
+  };
+  return b;
+}
+
+function render() {
+  const s = JSON.parse(game.state());
+  $("bank").textContent = `${s.bank}${s.currency}`;
+  $("dealer").textContent = s.dealer.map((c) => (c ? card(c) : "🂠")).join(" ");
+  $("dealer-total").textContent = s.dealer.length ? `(合計 ${s.dealer_total})` : "";
+  $("hands").replaceChildren(
+    ...s.hands.map((h, i) => {
+      const div = document.createElement("div");
+      div.className = "hand" + (i === s.active_hand ? " active" : "");
+      const result = h.outcome ? ` → ${OUTCOMES[h.outcome]}` : "";
+      div.innerHTML = `<span class="cards">${h.cards.map(card).join(" ")}</span> (合計 ${h.total}, ベット ${h.bet})${result}`;
+      return div;
+    }),
+  );
+
+  const controls = $("controls");
+  controls.replaceChildren();
+  if (s.phase === "betting") {
+    const input = document.createElement("input");
+    input.type = "number";
+    input.min = s.min_bet;
+    input.max = s.max_bet;
+    input.value = Math.min(10, s.max_bet);
+    controls.append(input, button("ベットして配る", () => game.bet(Number(input.value))));
+    if (s.last_result) {
+      $("message").textContent = `前回: ${s.last_result.net >= 0 ? "+" : ""}${s.last_result.net}${s.currency}`;
+    }
+  } else if (s.phase === "insurance") {
+    controls.append(
+      button(`インシュランス (${s.insurance_cost})`, () => game.insurance(true)),
+      button("見送る", () => game.insurance(false)),
+    );
+  } else if (s.phase === "player_turn") {
+    controls.append(...s.allowed_actions.map((a) => button(LABELS[a], () => game.act(a))));
+  } else {
+    $("message").textContent = "資金が足りません。ゲーム終了です";
+  }
+}
+
+render();
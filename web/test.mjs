@@ -0,0 +1,51 @@
+// Plays seeded rounds against the wasm build in headless Node:
+//   node web/test.mjs
+import { readFileSync } from "node:fs";
+import assert from "node:assert/strict";
+import { initSync, Game } from "./pkg/veintiuna.js";
+
+initSync({ module: readFileSync(new URL("./pkg/veintiuna_bg.wasm", import.meta.url)) });
+
+function playRound(game, bet) {
+  game.bet(bet);
+  let s = JSON.parse(game.state());
+  while (s.phase === "insurance" || s.phase === "player_turn") {
+    assert.equal(s.dealer[0], null, "hole card must stay hidden");
+    if (s.phase === "insurance") {
+      game.insurance(false);
+    } else {
+      const hand = s.hands[s.active_hand];
+      game.act(hand.total < 17 ? "hit" : "stand");
+    }
+    s = JSON.parse(game.state());
+  }
+  return s;
+}
+
+// seeded games repeat exactly
+const a = Game.withSeed(7);
+const b = Game.withSeed(7);
+for (let i = 0; i < 20; i++) {
+  assert.deepEqual(playRound(a, 10), playRound(b, 10));
+}
+
+// the bank follows the settled results
+const game = Game.withSeed(1);
+let bank = JSON.parse(game.state()).bank;
+for (let i = 0; i < 50; i++) {
+  const s = playRound(game, 10);
+  assert.equal(s.phase, "betting");
+  assert.ok(s.dealer.every((c) => c !== null), "hole card revealed after settlement");
+  assert.equal(s.bank, bank + s.last_result.net);
+  bank = s.bank;
+}
+
+// rule errors are thrown, not panics
+assert.throws(() => game.act("hit"), /アクション/);
+assert.throws(() => game.bet(0), /ベット額/);
+assert.throws(() => game.act("fly"));
+
+// unseeded games use crypto.getRandomValues
+new Game().bet(10);
+
+console.log("ok");